@@ -1,14 +1,26 @@
 use std::fmt::{self, Display, Formatter};
 use std::error;
 use std::str::FromStr;
+use std::rc::Rc;
+
+use itertools::Itertools;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+
+use crate::vm::Frame;
+use crate::lexer::Source;
 
 #[derive(Debug, Clone)]
 pub enum Error {
     IntParse(<i64 as FromStr>::Err, String),
     FloatParse(<f64 as FromStr>::Err, String),
     USizeParse(<usize as FromStr>::Err, String),
+    BigIntParse(<BigInt as FromStr>::Err, String),
+    RationalParse(<BigRational as FromStr>::Err, String),
     IllegalSym(String),
     Illformed(String),
+    Unterminated(String),
+    Io(String),
     Quit,
     StackUnderflow,
     OpType,
@@ -18,7 +30,28 @@ pub enum Error {
     IllNeg,
     IllNan,
     MissingKey(String),
-    LengthMismatch
+    LengthMismatch,
+    InvalidExit,
+    DictStackUnderflow,
+    InvalidRestore,
+    InvalidAccess,
+    UndefinedResult,
+    Stop,
+    Failed(Box<Failure>),
+    Read(Box<Error>, Source),
+}
+
+/// An error raised by a command, with the operands it was applied to,
+/// which have been left on the operand stack, where the command was
+/// read, if known, and where the procedures it was called from were
+/// called, innermost first.
+#[derive(Debug, Clone)]
+pub struct Failure {
+    pub error: Error,
+    pub command: String,
+    pub operands: Vec<Frame>,
+    pub location: Option<Rc<Source>>,
+    pub backtrace: Vec<Rc<Source>>,
 }
 
 impl<T> Into<Result<T, Error>> for Error {
@@ -37,14 +70,81 @@ impl Display for Error {
             Error::IntParse(err, s)   => write!(f, "Int parsing error: {err} ({s})"),
             Error::FloatParse(err, s) => write!(f, "Float parsing error: {err} ({s})"),
             Error::USizeParse(err, s) => write!(f, "USize parsing error: {err} ({s})"),
+            Error::BigIntParse(err, s) => write!(f, "BigInt parsing error: {err} ({s})"),
+            Error::RationalParse(err, s) => write!(f, "Rational parsing error: {err} ({s})"),
             Error::IllegalSym(string) => write!(f, "Illegal symbol: {string}"),
             Error::Illformed(string)  => write!(f, "Illformed string: {string}"),
+            Error::Unterminated(s)    => write!(f, "Unterminated at end of input: {s}"),
+            Error::Io(string)         => write!(f, "IO error: {string}"),
             Error::Range{len, index}  => write!(f, "Illegal range: len({len}), index({index})"),
             Error::IllNeg             => write!(f, "Illegal negative in range"),
             Error::IllNan             => write!(f, "Illegal NaN"),
             Error::Dropped            => write!(f, "Illegal reference to dropped object"),
             Error::MissingKey(s)      => write!(f, "Missing key {s} in Dict"),
             Error::LengthMismatch     => write!(f, "Array length mismatch in dyadic op"),
+            Error::InvalidExit        => write!(f, "Exit outside of a loop"),
+            Error::DictStackUnderflow => write!(f, "Dict stack underflow"),
+            Error::InvalidRestore     => write!(f, "Restore would leave dangling references"),
+            Error::InvalidAccess      => write!(f, "Write to a read-only object"),
+            Error::UndefinedResult    => write!(f, "Numeric result out of range or undefined"),
+            Error::Stop               => write!(f, "Stop outside of a stopped context"),
+            Error::Failed(failure)    => {
+                let Failure {error, command, operands, location, backtrace} = &**failure;
+                let operands = operands.iter().map(|frame| format!("{frame}")).join(" ");
+                write!(f, "{error} in {command} (operands: {operands})")?;
+                if let Some(location) = location {
+                    write!(f, " at {location}")?
+                };
+                for source in backtrace {
+                    write!(f, "\n    called from {source}")?
+                };
+                Ok(())
+            },
+            Error::Read(error, source) => write!(f, "{error} at {source}"),
+        }
+    }
+}
+
+impl Error {
+    /// The key under which a handler for this error is looked up in errordict.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Error::IntParse(..)       => "intparse",
+            Error::FloatParse(..)     => "floatparse",
+            Error::USizeParse(..)     => "usizeparse",
+            Error::BigIntParse(..)    => "bigintparse",
+            Error::RationalParse(..)  => "rationalparse",
+            Error::IllegalSym(_)      => "illegalsym",
+            Error::Illformed(_)       => "illformed",
+            Error::Unterminated(_)    => "unterminated",
+            Error::Io(_)              => "ioerror",
+            Error::Quit               => "quit",
+            Error::StackUnderflow     => "stackunderflow",
+            Error::OpType             => "optype",
+            Error::Unknown(_)         => "unknown",
+            Error::Range{..}          => "range",
+            Error::Dropped            => "dropped",
+            Error::IllNeg             => "illneg",
+            Error::IllNan             => "illnan",
+            Error::MissingKey(_)      => "missingkey",
+            Error::LengthMismatch     => "lengthmismatch",
+            Error::InvalidExit        => "invalidexit",
+            Error::DictStackUnderflow => "dictstackunderflow",
+            Error::InvalidRestore     => "invalidrestore",
+            Error::InvalidAccess      => "invalidaccess",
+            Error::UndefinedResult    => "undefinedresult",
+            Error::Stop               => "stop",
+            Error::Failed(failure)    => failure.error.name(),
+            Error::Read(error, _)     => error.name(),
+        }
+    }
+
+    /// The underlying error, without the command or source that raised it.
+    pub fn kind(&self) -> &Error {
+        match self {
+            Error::Failed(failure) => failure.error.kind(),
+            Error::Read(error, _)  => error.kind(),
+            error => error,
         }
     }
 }
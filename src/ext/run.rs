@@ -1,4 +1,3 @@
-use std::fs;
 use std::io::IsTerminal;
 use std::path::PathBuf;
 use std::error::Error;
@@ -45,13 +44,6 @@ impl Cli {
     }
 }
 
-fn from_file(reader: &mut Reader, path: PathBuf) -> MainResult {
-    match fs::read_to_string(path) {
-        Ok(string) => term::string::exec(reader, string),
-        Err(err) => Err(Box::new(err)),
-    }
-}
-
 fn default(reader: &mut Reader) -> MainResult {
     if std::io::stdout().is_terminal() {
         term::readline::exec(reader)
@@ -68,7 +60,7 @@ pub fn run() -> MainResult {
         let reader = &mut Reader::new(vm);
         match cli.command() {
             Command::Default        => default(reader),
-            Command::File{name}     => from_file(reader, name),
+            Command::File{name}     => term::file::exec(reader, name),
             Command::String{string} => term::string::exec(reader, string),
             Command::Line           => term::line::exec(reader),
             Command::Term           => term::readline::exec(reader),
@@ -1,19 +1,89 @@
+pub mod file;
 pub mod line;
 pub mod readline;
 pub mod string;
 
+use std::io::{self, BufRead, Read};
+
 use crate::error::*;
+use crate::lexer;
 use crate::reader::Reader;
 use super::run::MainResult;
 
+const PROMPT: &str = ">> ";
+const CONTINUATION: &str = ".. ";
+
+/// A source of lines that shows a prompt before reading each one.
+pub trait Prompted {
+    fn next_line(&mut self, prompt: &str) -> Option<String>;
+}
+
+/// Joins lines from a prompted source until they close every procedure,
+/// list, string and array they open, showing a continuation prompt
+/// for each line after the first.
+pub struct Statements<T> {
+    lines: T,
+}
+
+impl<T: Prompted> Statements<T> {
+    pub fn new(lines: T) -> Self {
+        Self {lines}
+    }
+}
+
+impl<T: Prompted> Iterator for Statements<T> {
+    type Item = String;
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = self.lines.next_line(PROMPT)?;
+        let mut continued = Continued {lines: &mut self.lines, statement: first + "\n", offset: 0};
+        lexer::complete(&mut continued);
+        let mut statement = continued.statement;
+        statement.pop();
+        Some(statement)
+    }
+}
+
+/// The lines of a statement as the lexer asks for them, so that each
+/// line is lexed once; every line ends in a newline, for it to stop at.
+struct Continued<'a, T> {
+    lines: &'a mut T,
+    statement: String,
+    offset: usize,
+}
+
+impl<T: Prompted> Read for Continued<'_, T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.fill_buf()?.read(buf)?;
+        self.consume(len);
+        Ok(len)
+    }
+}
+
+impl<T: Prompted> BufRead for Continued<'_, T> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.offset >= self.statement.len() {
+            if let Some(line) = self.lines.next_line(CONTINUATION) {
+                self.statement.push_str(&line);
+                self.statement.push('\n');
+            }
+        };
+        Ok(&self.statement.as_bytes()[self.offset..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.offset += amt
+    }
+}
+
 pub fn exec_string(reader: &mut Reader, string: String) -> Option<Result<(), Error>>
 {
-    let frames = match reader.parse(string) {
-        Ok(frames) => frames,
-        Err(err) => return Some(Err(err)),
-    };
-    
-    match reader.exec(frames) {
+    exec_input(reader, string.as_bytes(), "<string>")
+}
+
+pub fn exec_input<R: BufRead>(reader: &mut Reader, input: R, file: &str)
+    -> Option<Result<(), Error>>
+{
+    match reader.run(input, file) {
         Ok(_) => Some(Ok(())),
         Err(err) => {
             match err {
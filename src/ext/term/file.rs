@@ -0,0 +1,14 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+
+use super::*;
+use crate::reader::Reader;
+
+pub fn exec(reader: &mut Reader, path: PathBuf) -> MainResult {
+    let file = File::open(&path)?;
+    match exec_input(reader, BufReader::new(file), &path.display().to_string()) {
+        None|Some(Ok(())) => Ok(()),
+        Some(Err(err)) => Err(Box::new(err)),
+    }
+}
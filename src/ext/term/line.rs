@@ -14,10 +14,9 @@ impl Lines {
     }
 }
 
-impl Iterator for Lines {
-    type Item = String;
-    fn next(&mut self) -> Option<Self::Item> {
-        print!(">> ");
+impl Prompted for Lines {
+    fn next_line(&mut self, prompt: &str) -> Option<String> {
+        print!("{prompt}");
         io::stdout().flush().unwrap();
         match self.lines.next() {
             None => None,
@@ -28,5 +27,5 @@ impl Iterator for Lines {
 }
 
 pub fn exec(reader: &mut Reader) -> MainResult {
-    super::exec(reader, Lines::new())
+    super::exec(reader, Statements::new(Lines::new()))
 }
@@ -73,12 +73,11 @@ impl Drop for Lines {
 }
 
 
-impl Iterator for Lines {
-    type Item = String;
-    fn next(&mut self) -> Option<Self::Item> {
+impl Prompted for Lines {
+    fn next_line(&mut self, prompt: &str) -> Option<String> {
         io::stdout().flush().unwrap();
         let Self {editor, ..} = self; 
-        match editor.readline(">> ") {
+        match editor.readline(prompt) {
             Ok(line) => {
                 if let Err(err) = editor.add_history_entry(line.as_str()) {
                     eprintln!("Readline error: {err}")
@@ -99,7 +98,7 @@ impl Iterator for Lines {
 
 pub fn exec(reader: &mut Reader) -> MainResult {
     match Lines::new() {
-        Ok(lines) => super::exec(reader, lines),
+        Ok(lines) => super::exec(reader, Statements::new(lines)),
         Err(err) => {
             eprintln!("Readline error: {err}");
             Err(Box::new(err))
@@ -0,0 +1,644 @@
+use std::fmt;
+use std::io::BufRead;
+use std::rc::Rc;
+
+use crate::error::*;
+
+/// Where a token starts in its input; both line and column count from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Default for Position {
+    fn default() -> Self {Self {line: 1, column: 1}}
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Where a token was read: the file, or some other description of the
+/// input, and its position there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Source {
+    pub file: Rc<str>,
+    pub position: Position,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.file, self.position)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Float(String),
+    Int(String),
+    USize(String),
+    Byte(String),
+    Int32(String),
+    Float32(String),
+    Int128(String),
+    Complex(String),
+    BigInt(String),
+    Rational(String),
+    FloatArray(Vec<String>),
+    IntArray(Vec<String>),
+    USizeArray(Vec<String>),
+    ByteArray(Vec<String>),
+    Int32Array(Vec<String>),
+    Float32Array(Vec<String>),
+    Int128Array(Vec<String>),
+    ComplexArray(Vec<String>),
+    BoolArray(Vec<String>),
+    PName(String),
+    AName(String),
+    String(String),
+    PMark,
+    MkList,
+    AMark,
+    MkProc,
+}
+
+/// Splits a stream into tokens, reading a line at a time from input
+/// as tokens are requested.
+pub struct Lexer<R> {
+    input: R,
+    buffer: String,
+    offset: usize,
+    position: Position,
+    start: Position,
+    eof: bool,
+}
+
+fn delimiter(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '/' | '{' | '}' | '[' | ']' | '(' | ')')
+}
+
+fn digits(s: &[u8]) -> usize {
+    s.iter().take_while(|c| c.is_ascii_digit()).count()
+}
+
+fn sign(s: &[u8]) -> &[u8] {
+    match s.first() {
+        Some(b'+' | b'-') => &s[1..],
+        _ => s,
+    }
+}
+
+fn exponent(s: &[u8]) -> bool {
+    match s.first() {
+        Some(b'e' | b'E') => {
+            let s = sign(&s[1..]);
+            let n = digits(s);
+            n > 0 && n == s.len()
+        },
+        _ => false,
+    }
+}
+
+/// `[+-]?(\d+[eE][+-]?\d+|(\d+[.]\d*|[.]\d+)([eE][+-]?\d+)?)`
+fn is_float(s: &str) -> bool {
+    let s = sign(s.as_bytes());
+    let whole = digits(s);
+    let s = &s[whole..];
+    match s.first() {
+        Some(b'.') => {
+            let s = &s[1..];
+            let fraction = digits(s);
+            let s = &s[fraction..];
+            (whole > 0 || fraction > 0) && (s.is_empty() || exponent(s))
+        },
+        _ => whole > 0 && exponent(s),
+    }
+}
+
+/// The word without the `_` that may separate digits in radix.
+fn unseparated(word: &str, radix: u32) -> Option<String> {
+    let chars = word.chars().collect::<Vec<_>>();
+    let mut result = String::with_capacity(chars.len());
+    for (index, c) in chars.iter().enumerate() {
+        if *c != '_' {
+            result.push(*c);
+            continue
+        };
+        let between = index > 0
+            && chars[index-1].is_digit(radix)
+            && chars.get(index+1).is_some_and(|c| c.is_digit(radix));
+        if !between {return None};
+    };
+    Some(result)
+}
+
+/// An integer as decimal digits with an optional sign, or `*` for NaN.
+/// Digits may be separated by `_`, and written in another radix
+/// as `16#FF`, `0xFF` or `0b11`.
+fn integer(word: &str, signed: bool) -> Option<String> {
+    if word == "*" {return Some(word.into())};
+
+    let (sign, unsigned) = match word.as_bytes().first() {
+        Some(b'-') if signed => ("-", &word[1..]),
+        Some(b'+') if signed => ("", &word[1..]),
+        _ => ("", word),
+    };
+    let (radix, digits) = if let Some(digits) = unsigned.strip_prefix("0x") {
+        (16, digits)
+    } else if let Some(digits) = unsigned.strip_prefix("0b") {
+        (2, digits)
+    } else if let Some((radix, digits)) = unsigned.split_once('#') {
+        let radix = radix.parse::<u32>().ok().filter(|radix| (2..=36).contains(radix))?;
+        (radix, digits)
+    } else {
+        (10, unsigned)
+    };
+
+    let digits = unseparated(digits, radix)
+        .filter(|digits| !digits.is_empty() && digits.chars().all(|c| c.is_digit(radix)))?;
+    if radix == 10 {return Some(format!("{sign}{digits}"))};
+    match u128::from_str_radix(&digits, radix) {
+        Ok(value) => Some(format!("{sign}{value}")),
+        // left for parsing to report as out of range
+        Err(_) => Some(word.into()),
+    }
+}
+
+/// A float as Rust would read it, or `*` for NaN;
+/// digits may be separated by `_`, and `inf` is infinity.
+fn float(word: &str) -> Option<String> {
+    match word {
+        "*" | "inf" | "-inf" => return Some(word.into()),
+        "+inf" => return Some("inf".into()),
+        _ => (),
+    };
+
+    let result = unseparated(word, 10)?;
+    is_float(&result).then_some(result)
+}
+
+/// A part of a complex number: a real with an optional sign, or `*`.
+fn real(word: &str) -> Option<String> {
+    match word.strip_prefix(['+', '-']) {
+        Some("*") => Some("*".into()),
+        _ => integer(word, true).or_else(|| float(word)),
+    }
+}
+
+/// A complex number as `1+2i` or `2i` without its `i`, given as the real
+/// and imaginary parts separated by a space, or `*` if both are NaN.
+fn complex(word: &str) -> Option<String> {
+    let split = word.char_indices().rev().find(|&(index, c)| {
+        index > 0 && matches!(c, '+' | '-') && !word[..index].ends_with(['e', 'E'])
+    });
+    let (re, im) = match split {
+        Some((index, _)) => (real(&word[..index])?, real(&word[index..])?),
+        None => ("0".into(), real(word)?),
+    };
+    Some(if re == "*" && im == "*" {re} else {format!("{re} {im}")})
+}
+
+/// A rational as `-3:4`, given as `-3/4`; neither part may be `*`.
+fn rational(word: &str) -> Option<String> {
+    let (numer, denom) = word.split_once(':')?;
+    let numer = integer(numer, true).filter(|numer| numer != "*")?;
+    let denom = integer(denom, false).filter(|denom| denom != "*")?;
+    Some(format!("{numer}/{denom}"))
+}
+
+/// Drops an optional type suffix from a number.
+fn unsuffix(s: &str, suffix: char) -> &str {
+    s.strip_suffix(suffix).unwrap_or(s)
+}
+
+/// Suffixes are only taken as such if the word is not a number without
+/// them, as `d` is a digit in `16#1d`; a lone `*` is an integer NaN.
+fn number(word: String) -> Token {
+    if let Some(value) = integer(&word, true) {
+        Token::Int(value)
+    } else if let Some(value) = float(unsuffix(&word, 'd')) {
+        Token::Float(value)
+    } else if let Some(value) = word.strip_suffix('u').and_then(|word| integer(word, false)) {
+        Token::USize(value)
+    } else if let Some(value) = word.strip_suffix('l').and_then(|word| integer(word, true)) {
+        Token::Int(value)
+    } else if let Some(value) = word.strip_suffix('b').and_then(|word| integer(word, false)) {
+        Token::Byte(value)
+    } else if let Some(value) = word.strip_suffix('s').and_then(|word| integer(word, true)) {
+        Token::Int32(value)
+    } else if let Some(value) = word.strip_suffix('f').and_then(float) {
+        Token::Float32(value)
+    } else if let Some(value) = word.strip_suffix('q').and_then(|word| integer(word, true)) {
+        Token::Int128(value)
+    } else if let Some(value) = word.strip_suffix('i').and_then(complex) {
+        Token::Complex(value)
+    } else if let Some(value) = word.strip_suffix('n').and_then(|word| integer(word, true)) {
+        Token::BigInt(value)
+    } else if let Some(value) = rational(&word) {
+        Token::Rational(value)
+    } else {
+        Token::AName(word)
+    }
+}
+
+/// Checks an array element against the syntax of its element type.
+fn element(kind: char, element: &str) -> Option<String> {
+    let typed = |suffix, parse: &dyn Fn(&str) -> Option<String>| {
+        parse(element).or_else(|| parse(element.strip_suffix(suffix)?))
+    };
+    match kind {
+        'd' => typed('d', &float),
+        'l' => typed('l', &|word| integer(word, true)),
+        'b' => typed('b', &|word| integer(word, false)),
+        's' => typed('s', &|word| integer(word, true)),
+        'f' => typed('f', &float),
+        'q' => typed('q', &|word| integer(word, true)),
+        't' => matches!(element, "true" | "false" | "*").then(|| element.into()),
+        'c' => element.strip_suffix('i').and_then(complex)
+            .or_else(|| real(element).map(|re| if re == "*" {re} else {format!("{re} 0")})),
+        _   => typed('u', &|word| integer(word, false)),
+    }
+}
+
+fn push_char(bytes: &mut Vec<u8>, c: char) {
+    bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes())
+}
+
+/// Strings are read as bytes, from escapes, hex or ASCII85,
+/// but must spell out UTF-8.
+fn utf8(bytes: Vec<u8>) -> Result<String, Error> {
+    String::from_utf8(bytes).map_err(|err| {
+        let string = String::from_utf8_lossy(err.as_bytes());
+        Error::Illformed(format!("invalid UTF-8 in string {string}"))
+    })
+}
+
+/// Four bytes from five base 85 digits.
+fn base85(group: &[u32]) -> Result<[u8; 4], Error> {
+    let value = group.iter().fold(0u64, |value, digit| value*85 + *digit as u64);
+    match u32::try_from(value) {
+        Ok(value) => Ok(value.to_be_bytes()),
+        Err(_) => Err(Error::Illformed("ASCII85 string group overflow".into())),
+    }
+}
+
+impl<R: BufRead> Lexer<R> {
+    pub fn new(input: R) -> Self {
+        Self {
+            input,
+            buffer: String::new(),
+            offset: 0,
+            position: Position::default(),
+            start: Position::default(),
+            eof: false,
+        }
+    }
+
+    /// The position of the next unread character.
+    pub fn position(&self) -> Position {self.position}
+
+    /// Where the last token, or the failed attempt at one, started.
+    pub fn start(&self) -> Position {self.start}
+
+    /// Reads lines until there is something left to lex;
+    /// false at the end of the input.
+    fn fill(&mut self) -> Result<bool, Error> {
+        while self.offset >= self.buffer.len() {
+            if self.eof {return Ok(false)};
+            self.buffer.clear();
+            self.offset = 0;
+            match self.input.read_line(&mut self.buffer) {
+                Ok(0) => self.eof = true,
+                Ok(_) => (),
+                Err(err) => return Err(Error::Io(err.to_string())),
+            }
+        };
+        Ok(true)
+    }
+
+    fn peek(&mut self) -> Result<Option<char>, Error> {
+        if !self.fill()? {return Ok(None)};
+        Ok(self.buffer[self.offset..].chars().next())
+    }
+
+    /// The character after the next one, if it is on the current line.
+    fn peek_second(&self) -> Option<char> {
+        self.buffer[self.offset..].chars().nth(1)
+    }
+
+    fn bump(&mut self) -> Result<Option<char>, Error> {
+        let Some(c) = self.peek()? else {return Ok(None)};
+        self.offset += c.len_utf8();
+        if c == '\n' {
+            self.position.line += 1;
+            self.position.column = 1;
+        } else {
+            self.position.column += 1;
+        };
+        Ok(Some(c))
+    }
+
+    fn skip_comment(&mut self) -> Result<(), Error> {
+        while let Some(c) = self.bump()? {
+            if c == '\n' {break};
+        };
+        Ok(())
+    }
+
+    fn word(&mut self, mut word: String, end: fn(char) -> bool) -> Result<String, Error> {
+        while let Some(c) = self.peek()? {
+            if end(c) {break};
+            word.push(c);
+            self.bump()?;
+        };
+        Ok(word)
+    }
+
+    /// The body of a `(string)`, which may run over several lines;
+    /// balanced parentheses need no escapes.
+    fn string(&mut self) -> Result<String, Error> {
+        let mut bytes = Vec::new();
+        let mut depth = 0usize;
+        loop {
+            let Some(c) = self.bump()? else {
+                let string = String::from_utf8_lossy(&bytes);
+                return Err(Error::Unterminated(format!("({string}")))
+            };
+            match c {
+                '\\' => {
+                    self.escape(&mut bytes)?;
+                    continue
+                },
+                '(' => depth += 1,
+                ')' if depth == 0 => return utf8(bytes),
+                ')' => depth -= 1,
+                _ => (),
+            };
+            push_char(&mut bytes, c);
+        }
+    }
+
+    /// The character after a backslash in a string: `\n`, `\r`, `\t`,
+    /// `\b`, `\f`, `\\`, `\(`, `\)`, up to three octal digits for a byte,
+    /// `\u{...}` for a unicode character, or a newline to be dropped.
+    /// The backslash before any other character is dropped.
+    fn escape(&mut self, bytes: &mut Vec<u8>) -> Result<(), Error> {
+        let Some(c) = self.bump()? else {return Ok(())};
+        let byte = match c {
+            'n' => b'\n',
+            'r' => b'\r',
+            't' => b'\t',
+            'b' => 0x08,
+            'f' => 0x0c,
+            '\n' => return Ok(()),
+            '\r' => {
+                if self.peek()? == Some('\n') {self.bump()?;};
+                return Ok(())
+            },
+            '0'..='7' => {
+                let mut value = c as u32 - '0' as u32;
+                for _ in 0..2 {
+                    let Some(digit) = self.peek()?.and_then(|c| c.to_digit(8)) else {break};
+                    value = value*8 + digit;
+                    self.bump()?;
+                };
+                // as in PostScript, overflow past a byte is ignored
+                value as u8
+            },
+            'u' if self.peek()? == Some('{') => {
+                self.bump()?;
+                let hex = self.word(String::new(), |c| c == '}' || c.is_whitespace())?;
+                let c = match self.bump()? {
+                    Some('}') => u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32),
+                    _ => None,
+                };
+                let Some(c) = c else {
+                    return Err(Error::Illformed(format!("\\u{{{hex}")))
+                };
+                push_char(bytes, c);
+                return Ok(())
+            },
+            c => {
+                push_char(bytes, c);
+                return Ok(())
+            },
+        };
+        bytes.push(byte);
+        Ok(())
+    }
+
+    /// The body of a `<hex>` string; whitespace is ignored and an odd
+    /// last digit is followed by 0.
+    fn hex(&mut self) -> Result<String, Error> {
+        let mut digits = Vec::new();
+        loop {
+            match self.bump()? {
+                None => {
+                    let digits = digits.iter().map(|d| format!("{d:x}")).collect::<String>();
+                    return Err(Error::Unterminated(format!("<{digits}")))
+                },
+                Some('>') => break,
+                Some(c) if c.is_whitespace() => (),
+                Some(c) => match c.to_digit(16) {
+                    Some(digit) => digits.push(digit as u8),
+                    None => return Err(Error::Illformed(format!("hex string digit {c}"))),
+                },
+            }
+        };
+        if digits.len() % 2 == 1 {digits.push(0)};
+        utf8(digits.chunks(2).map(|pair| pair[0]*16 + pair[1]).collect())
+    }
+
+    /// The body of a `<~ASCII85~>` string; whitespace is ignored.
+    fn ascii85(&mut self) -> Result<String, Error> {
+        let mut bytes = Vec::new();
+        let mut group = Vec::with_capacity(5);
+        loop {
+            match self.bump()? {
+                None => {
+                    let string = String::from_utf8_lossy(&bytes);
+                    return Err(Error::Unterminated(format!("<~{string}")))
+                },
+                Some('~') => match self.bump()? {
+                    Some('>') => break,
+                    _ => return Err(Error::Illformed("ASCII85 string ~".into())),
+                },
+                Some(c) if c.is_whitespace() => (),
+                Some('z') if group.is_empty() => bytes.extend([0; 4]),
+                Some(c@'!'..='u') => {
+                    group.push(c as u32 - '!' as u32);
+                    if group.len() == 5 {
+                        bytes.extend(base85(&group)?);
+                        group.clear();
+                    }
+                },
+                Some(c) => return Err(Error::Illformed(format!("ASCII85 string {c}"))),
+            }
+        };
+
+        match group.len() {
+            0 => (),
+            1 => return Err(Error::Illformed("ASCII85 string final group".into())),
+            len => {
+                group.resize(5, 84);
+                bytes.extend(&base85(&group)?[..len-1]);
+            },
+        };
+        utf8(bytes)
+    }
+
+    /// The elements of an array such as `<d ...>`, `<l ...>` or `<t ...>`,
+    /// which may run over several lines.
+    fn array(&mut self, kind: char) -> Result<Vec<String>, Error> {
+        let mut elements = Vec::new();
+        loop {
+            match self.bump()? {
+                None => {
+                    let elements = elements.join(" ");
+                    return Err(Error::Unterminated(format!("<{kind} {elements}")))
+                },
+                Some('>') => return Ok(elements),
+                Some(c) if c.is_whitespace() => (),
+                Some(c) => {
+                    let word = self.word(c.into(), |c| c.is_whitespace() || c == '>')?;
+                    match element(kind, &word) {
+                        Some(value) => elements.push(value),
+                        None => return Err(Error::Illformed(word)),
+                    }
+                },
+            }
+        }
+    }
+
+    /// The next token that starts on the current line, reading the first
+    /// line if none has been read; None at the end of the line. The token
+    /// may run over more lines.
+    fn line_token(&mut self) -> Result<Option<(Position, Token)>, Error> {
+        if self.buffer.is_empty() && !self.fill()? {
+            return Ok(None)
+        };
+        loop {
+            match self.buffer[self.offset..].chars().next() {
+                None => return Ok(None),
+                Some('|') => self.skip_comment()?,
+                Some(c) if c.is_whitespace() => {self.bump()?;},
+                Some(_) => return self.token(),
+            }
+        }
+    }
+
+    /// The next token and where it starts, or None at the end of input.
+    pub fn token(&mut self) -> Result<Option<(Position, Token)>, Error> {
+        loop {
+            match self.peek()? {
+                None => return Ok(None),
+                Some('|') => self.skip_comment()?,
+                Some(c) if c.is_whitespace() => {self.bump()?;},
+                Some(_) => break,
+            }
+        };
+
+        self.start = self.position;
+        let Some(c) = self.bump()? else {return Ok(None)};
+        let token = match c {
+            '[' => Token::PMark,
+            ']' => Token::MkList,
+            '{' => Token::AMark,
+            '}' => Token::MkProc,
+            '(' => Token::String(self.string()?),
+            ')' => return Err(Error::IllegalSym(self.word(c.into(), char::is_whitespace)?)),
+            '/' => match self.word(String::new(), delimiter)? {
+                name if name.is_empty() => return Err(Error::IllegalSym("/".into())),
+                name => Token::PName(name),
+            },
+            '<' if self.peek()? == Some('~') => {
+                self.bump()?;
+                Token::String(self.ascii85()?)
+            },
+            // a tag alone before whitespace or `>` makes an array, as `<d>` did
+            // before there were hex strings. The tags b, c, d and f are hex
+            // digits too, so `<b 1>` or `<f>`, once hex strings, are arrays
+            // now; a hex string that starts with such a digit alone is
+            // written with a space after the `<`, as `< b 1>`
+            '<' if matches!(self.peek()?, Some('d' | 'l' | 'u' | 'b' | 's' | 'f' | 'q' | 'c' | 't'))
+                && self.peek_second().is_none_or(|c| c.is_whitespace() || c == '>') =>
+            {
+                let Some(kind) = self.bump()? else {unreachable!()};
+                let elements = self.array(kind)?;
+                match kind {
+                    'd' => Token::FloatArray(elements),
+                    'l' => Token::IntArray(elements),
+                    'b' => Token::ByteArray(elements),
+                    's' => Token::Int32Array(elements),
+                    'f' => Token::Float32Array(elements),
+                    'q' => Token::Int128Array(elements),
+                    'c' => Token::ComplexArray(elements),
+                    't' => Token::BoolArray(elements),
+                    _   => Token::USizeArray(elements),
+                }
+            },
+            '<' if self.peek()?
+                .is_none_or(|c| c.is_ascii_hexdigit() || c.is_whitespace() || c == '>') =>
+            {
+                Token::String(self.hex()?)
+            },
+            c => number(self.word(c.into(), delimiter)?),
+        };
+        Ok(Some((self.start, token)))
+    }
+}
+
+impl<R: BufRead> Iterator for Lexer<R> {
+    type Item = Result<(Position, Token), Error>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.token().transpose()
+    }
+}
+
+/// Whether input closes every procedure, list, string and array it
+/// opens, or whether more lines are needed to finish it. Lines are read
+/// only as the lexer needs them, so none past the one that closes the
+/// last thing opened.
+pub fn complete<R: BufRead>(input: R) -> bool {
+    let mut lexer = Lexer::new(input);
+    let mut depth = 0isize;
+    loop {
+        match lexer.line_token() {
+            Ok(Some((_, Token::AMark | Token::PMark))) => depth += 1,
+            Ok(Some((_, Token::MkProc | Token::MkList))) => depth -= 1,
+            Ok(Some(_)) => (),
+            Ok(None) if depth <= 0 => return true,
+            Ok(None) => match lexer.fill() {
+                Ok(true) => (),
+                Ok(false) | Err(_) => return false,
+            },
+            Err(Error::Unterminated(_)) => return false,
+            Err(_) => return true,
+        }
+    }
+}
+
+/// A string in the syntax that reads back as it, without the parentheses.
+pub fn escape(string: &str) -> String {
+    let mut escaped = String::with_capacity(string.len());
+    for c in string.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            },
+            '\n'     => escaped.push_str("\\n"),
+            '\r'     => escaped.push_str("\\r"),
+            '\t'     => escaped.push_str("\\t"),
+            '\u{8}'  => escaped.push_str("\\b"),
+            '\u{c}'  => escaped.push_str("\\f"),
+            c if c.is_ascii_control() => escaped.push_str(&format!("\\{:03o}", c as u32)),
+            c if c.is_control() => escaped.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => escaped.push(c),
+        }
+    };
+    escaped
+}
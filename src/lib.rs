@@ -1,5 +1,6 @@
 pub mod error;
 pub mod ext;
+pub mod lexer;
 pub mod numeric;
 pub mod reader;
 pub mod types;
@@ -1,14 +1,23 @@
 use std::fmt::{self, Debug};
 use std::marker::PhantomData;
 use itertools::Itertools;
-use num_traits::cast::cast;
+use num_traits::cast::{cast, AsPrimitive};
 use paste::paste;
 
 pub mod ops;
 pub mod ops_defs;
 pub mod primitive;
+pub mod reduce;
+pub mod linalg;
+pub mod strided;
+pub mod complex;
+pub mod exact;
+pub mod policy;
 
 use primitive::NumericPrimitive;
+use complex::Complex;
+use crate::types::bool::Bool;
+use crate::error::Error;
 
 pub trait CastFromFloat: NumericPrimitive {
     fn cast(f: f64) -> Option<Self>;
@@ -18,6 +27,13 @@ impl CastFromFloat for f64 {
     fn cast(f: f64) -> Option<Self> {Some(f)}
 }
 
+impl CastFromFloat for f32 {
+    fn cast(f: f64) -> Option<Self> {
+        let r = f as f32;
+        if f.is_finite() && !r.is_finite() {None} else {Some(r)}
+    }
+}
+
 macro_rules! cast_from_float {
     ($($prim:ident),+) => {
         $(
@@ -35,7 +51,7 @@ macro_rules! cast_from_float {
     };
 }
 
-cast_from_float!(i64, usize, i128);
+cast_from_float!(i64, usize, i128, u8, i32);
 
 pub trait CastFrom128: NumericPrimitive {
     fn cast(f: i128) -> Option<Self>;
@@ -55,7 +71,11 @@ macro_rules! cast_from_128 {
     };
 }
 
-cast_from_128!(i64, usize);
+cast_from_128!(i64, usize, u8, i32);
+
+impl CastFrom128 for i128 {
+    fn cast(i: i128) -> Option<Self> {Some(i)}
+}
 
 pub struct CasterBuilder<T: NumericPrimitive + CastFromFloat> {
     lhs: PhantomData<T>,
@@ -87,10 +107,15 @@ macro_rules! caster_builder {
     };
 }
 
-caster_builder_trait!(i64, f64, usize);
-caster_builder!(i64 => (i64, usize, f64));
-caster_builder!(usize => (i64, usize, f64));
-caster_builder!(f64 => (i64, usize, f64));
+caster_builder_trait!(i64, f64, usize, u8, i32, f32, i128, Complex);
+caster_builder!(i64     => (i64, f64, usize, u8, i32, f32, i128, Complex));
+caster_builder!(usize   => (i64, f64, usize, u8, i32, f32, i128, Complex));
+caster_builder!(f64     => (i64, f64, usize, u8, i32, f32, i128, Complex));
+caster_builder!(u8      => (i64, f64, usize, u8, i32, f32, i128, Complex));
+caster_builder!(i32     => (i64, f64, usize, u8, i32, f32, i128, Complex));
+caster_builder!(f32     => (i64, f64, usize, u8, i32, f32, i128, Complex));
+caster_builder!(i128    => (i64, f64, usize, u8, i32, f32, i128, Complex));
+caster_builder!(Complex => (i64, f64, usize, u8, i32, f32, i128, Complex));
 
 #[derive(Clone, Copy)]
 pub struct Caster<T, U> where
@@ -144,21 +169,84 @@ macro_rules! caster_back {
     };
 }
 
+// Integers of one type are worked on as such, and of two types as i128;
+// anything with a float is worked on as f64, and cast back to the left.
 caster_simple!(
     (i64,   i64)   => i64,
     (usize, usize) => usize,
+    (u8,    u8)    => u8,
+    (i32,   i32)   => i32,
+    (i128,  i128)  => i128,
     (f64,   f64)   => f64,
+    (f64,   f32)   => f64,
     (f64,   i64)   => f64,
-    (f64,   usize) => f64
+    (f64,   usize) => f64,
+    (f64,   u8)    => f64,
+    (f64,   i32)   => f64,
+    (f64,   i128)  => f64
 );
 
 caster_back!(
     (i64,   f64)   => f64  CastFromFloat::cast,
     (usize, f64)   => f64  CastFromFloat::cast,
+    (u8,    f64)   => f64  CastFromFloat::cast,
+    (i32,   f64)   => f64  CastFromFloat::cast,
+    (i128,  f64)   => f64  CastFromFloat::cast,
+    (i64,   f32)   => f64  CastFromFloat::cast,
+    (usize, f32)   => f64  CastFromFloat::cast,
+    (u8,    f32)   => f64  CastFromFloat::cast,
+    (i32,   f32)   => f64  CastFromFloat::cast,
+    (i128,  f32)   => f64  CastFromFloat::cast,
+    (f32,   f64)   => f64  CastFromFloat::cast,
+    (f32,   f32)   => f64  CastFromFloat::cast,
+    (f32,   i64)   => f64  CastFromFloat::cast,
+    (f32,   usize) => f64  CastFromFloat::cast,
+    (f32,   u8)    => f64  CastFromFloat::cast,
+    (f32,   i32)   => f64  CastFromFloat::cast,
+    (f32,   i128)  => f64  CastFromFloat::cast,
     (i64,   usize) => i128 CastFrom128::cast,
-    (usize, i64)   => i128 CastFrom128::cast
+    (i64,   u8)    => i128 CastFrom128::cast,
+    (i64,   i32)   => i128 CastFrom128::cast,
+    (i64,   i128)  => i128 CastFrom128::cast,
+    (usize, i64)   => i128 CastFrom128::cast,
+    (usize, u8)    => i128 CastFrom128::cast,
+    (usize, i32)   => i128 CastFrom128::cast,
+    (usize, i128)  => i128 CastFrom128::cast,
+    (u8,    i64)   => i128 CastFrom128::cast,
+    (u8,    usize) => i128 CastFrom128::cast,
+    (u8,    i32)   => i128 CastFrom128::cast,
+    (u8,    i128)  => i128 CastFrom128::cast,
+    (i32,   i64)   => i128 CastFrom128::cast,
+    (i32,   usize) => i128 CastFrom128::cast,
+    (i32,   u8)    => i128 CastFrom128::cast,
+    (i32,   i128)  => i128 CastFrom128::cast,
+    (i128,  i64)   => i128 CastFrom128::cast,
+    (i128,  usize) => i128 CastFrom128::cast,
+    (i128,  u8)    => i128 CastFrom128::cast,
+    (i128,  i32)   => i128 CastFrom128::cast
 );
 
+// With a complex number on either side both are worked on as such, and
+// cast back to a real on the left only without an imaginary part.
+macro_rules! caster_complex {
+    ($($prim:ty),+) => {
+        caster_base!(
+            $(
+                (Complex, $prim, Complex,
+                 |lhs, rhs: $prim| {(lhs, Complex::new(rhs.as_(), 0.0))},
+                 Some),
+                ($prim, Complex, Complex,
+                 |lhs: $prim, rhs| {(Complex::new(lhs.as_(), 0.0), rhs)},
+                 |mid: Complex| {mid.real().and_then(CastFromFloat::cast)})
+            ),+
+        );
+    };
+}
+
+caster_complex!(i64, f64, usize, u8, i32, f32, i128);
+
+caster_base!((Complex, Complex, Complex, |lhs, rhs| {(lhs, rhs)}, Some));
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum NumericValue<T: NumericPrimitive> {
     Value(T),
@@ -176,12 +264,18 @@ impl<T: NumericPrimitive> NumericValue<T> {
         }
     }
     pub fn from_primitive(value: T) -> Self {Value(value)}
-    pub fn to_value<U: NumericPrimitive>(self) -> NumericValue<U> {
-        if let Value(value) = self {
-            if let Some(value) = cast::<T, U>(value) {
-                Value(value)
-            } else {NaN}
-        } else {NaN}
+    /// Values out of range for U are settled by the numeric policy,
+    /// including finite floats that a narrower float would take for
+    /// infinite, as CastFromFloat for f32 has it. Floats become integers
+    /// rounded to the nearest, halves away from zero, as there too.
+    pub fn to_value<U: NumericPrimitive>(self) -> Result<NumericValue<U>, Error> {
+        let Value(value) = self else {return Ok(NaN)};
+        let value = if U::INTEGRAL {value.round_nearest()} else {value};
+        let finite = |value: f64| value.is_finite();
+        match cast::<T, U>(value) {
+            Some(cast) if finite(value.as_()) == finite(cast.as_()) => Ok(Value(cast)),
+            _ => policy::settle_cast(value),
+        }
     }
 }
 
@@ -196,7 +290,7 @@ impl<T: NumericPrimitive> fmt::Display for NumericValue<T> {
 
 pub mod cardinality {
     pub type Scalar<T> = super::NumericValue<T>; //where T: super::NumericPrimitive;
-    pub type Array<T> = Vec<Scalar<T>>; //where T: super::NumericPrimitive;
+    pub type Array<T> = super::strided::Strided<T>; //where T: super::NumericPrimitive;
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -217,12 +311,86 @@ impl<T> From<cardinality::Array<T>> for Number<T> where
     fn from(item: cardinality::Array<T>) -> Number<T> {Array(item)}
 }
 
+impl<T> From<Vec<cardinality::Scalar<T>>> for Number<T> where
+    T: NumericPrimitive {
+    fn from(item: Vec<cardinality::Scalar<T>>) -> Number<T> {Array(item.into())}
+}
+
+impl<T: NumericPrimitive> Number<T> {
+    /// Converts element-wise, with values out of range for U settled
+    /// by the numeric policy.
+    pub fn to_number<U: NumericPrimitive>(self) -> Result<Number<U>, Error> {
+        match self {
+            Scalar(scalar) => Ok(Scalar(scalar.to_value()?)),
+            Array(array)   => Ok(array.to_vec().into_iter().map(NumericValue::to_value).try_collect::<_, Vec<_>, _>()?.into()),
+        }
+    }
+
+    /// Marks an array as lent to an operator by the operand stack,
+    /// or as given back.
+    pub fn lend(&mut self, lent: bool) {
+        if let Array(array) = self {
+            array.lend(lent)
+        }
+    }
+
+    pub fn cardinality(&self) -> &'static str {
+        match self {
+            Scalar(_) => "scalar",
+            Array(_)  => "array",
+        }
+    }
+
+    /// Non-zero elements are true and NaNs unknown.
+    pub fn truth(&self) -> Bool {
+        let truth = |value: &cardinality::Scalar<T>| match value {
+            Value(value) => Some(!value.is_zero()),
+            NaN => None,
+        };
+        match self {
+            Scalar(scalar) => truth(scalar).into(),
+            Array(array)   => array.to_vec().iter().map(truth).collect_vec().into(),
+        }
+    }
+
+    /// The elements, a scalar being a single one.
+    pub fn to_vec(&self) -> Vec<cardinality::Scalar<T>> {
+        match self {
+            Scalar(scalar) => vec![*scalar],
+            Array(array)   => array.to_vec(),
+        }
+    }
+
+    pub fn concat(&self, other: &Self) -> Self {
+        let mut values = self.to_vec();
+        values.extend(other.to_vec());
+        values.into()
+    }
+
+    pub fn reverse(&self) -> Self {
+        self.to_vec().into_iter().rev().collect_vec().into()
+    }
+
+    /// Each element count times over.
+    pub fn repeat(&self, count: usize) -> Self {
+        self.to_vec().into_iter()
+            .flat_map(|value| std::iter::repeat_n(value, count))
+            .collect_vec()
+            .into()
+    }
+
+    /// All the elements count times over.
+    pub fn tile(&self, count: usize) -> Self {
+        self.to_vec().repeat(count).into()
+    }
+}
+
 impl<T: NumericPrimitive> fmt::Display for Number<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Scalar(scalar) => write!(f, "{scalar}"),
             Array(array) => {
-                let s = array.iter().map(|scalar| format!("{scalar}")).join(" ");
+                let s = array.to_vec().iter().map(|scalar| format!("{scalar}")).join(" ");
                 f.write_str(s.as_str())
             },
         }
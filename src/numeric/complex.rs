@@ -0,0 +1,297 @@
+use std::fmt;
+use std::ops;
+use std::str::FromStr;
+use std::num::ParseFloatError;
+
+use itertools::Itertools;
+use num_traits::{Zero, One, ToPrimitive, NumCast};
+use num_traits::cast::AsPrimitive;
+
+use crate::error::Error;
+use super::{cardinality, Number, Scalar, Array, Value, NaN, CastFromFloat};
+use super::primitive::*;
+
+/// A complex number of two floats. Either part may be NaN on its own,
+/// as when read from `*+2i`; with both NaN the whole number is.
+#[derive(Debug, Clone, Copy)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+fn finite(value: f64) -> f64 {
+    if value.is_finite() {value} else {f64::NAN}
+}
+
+impl Complex {
+    /// Anything not finite in either part comes out as NaN.
+    pub fn new(re: f64, im: f64) -> Self {
+        Self {re: finite(re), im: finite(im)}
+    }
+
+    pub fn from_polar(r: f64, theta: f64) -> Self {
+        Self::new(r*theta.cos(), r*theta.sin())
+    }
+
+    pub fn re(self) -> f64 {self.re}
+    pub fn im(self) -> f64 {self.im}
+    pub fn norm(self) -> f64 {self.re.hypot(self.im)}
+    pub fn arg(self) -> f64 {self.im.atan2(self.re)}
+    pub fn conj(self) -> Self {Self::new(self.re, -self.im)}
+
+    /// The real part if there is no imaginary one.
+    pub fn real(self) -> Option<f64> {
+        (self.im == 0.0 && self.re.is_finite()).then_some(self.re)
+    }
+
+    /// The result of an operation on operands, with any part that is
+    /// not finite NaN; None, for the numeric policy to settle, if both
+    /// parts are NaN or a part of finite operands overflowed or came out
+    /// NaN.
+    fn checked(self, operands: &[Complex]) -> Option<Self> {
+        let finite = |value: &Complex| value.re.is_finite() && value.im.is_finite();
+        if operands.iter().all(finite) && !finite(&self) {return None};
+        let value = Self::new(self.re, self.im);
+        if value.re.is_nan() && value.im.is_nan() {None} else {Some(value)}
+    }
+
+    fn ln(self) -> Self {Self::new(self.norm().ln(), self.arg())}
+    fn exp(self) -> Self {Self::from_polar(self.re.exp(), self.im)}
+
+    fn powi(self, exp: i64) -> Self {
+        let (mut base, mut bits, mut power) = (self, exp.unsigned_abs(), Self::one());
+        while bits > 0 {
+            if bits & 1 == 1 {power = power*base};
+            base = base*base;
+            bits >>= 1;
+        };
+        if exp < 0 {Self::one()/power} else {power}
+    }
+
+    fn map(self, f: fn(f64) -> f64) -> Self {
+        Self::new(f(self.re), f(self.im))
+    }
+}
+
+/// Equal where both parts are, or are both NaN.
+impl PartialEq for Complex {
+    fn eq(&self, other: &Self) -> bool {
+        let eq = |lhs: f64, rhs: f64| lhs == rhs || (lhs.is_nan() && rhs.is_nan());
+        eq(self.re, other.re) && eq(self.im, other.im)
+    }
+}
+
+/// Ordered only on the real line: off it, numbers are equal or unordered.
+impl PartialOrd for Complex {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        if self == other {
+            Some(std::cmp::Ordering::Equal)
+        } else if self.im == 0.0 && other.im == 0.0 {
+            self.re.partial_cmp(&other.re)
+        } else {None}
+    }
+}
+
+/// As read back: `1+2i`, `1-2i`, with `*` for a NaN part.
+impl fmt::Display for Complex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let part = |value: f64| if value.is_nan() {"*".into()} else {format!("{value}")};
+        let sign = if self.im.is_sign_negative() && !self.im.is_nan() {'-'} else {'+'};
+        write!(f, "{}{sign}{}i", part(self.re), part(self.im.abs()))
+    }
+}
+
+/// Parses the real and imaginary parts separated by a space, as the
+/// lexer leaves them, or a lone real part; either may be `*`.
+impl FromStr for Complex {
+    type Err = ParseFloatError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let part = |s: &str| if s == "*" {Ok(f64::NAN)} else {s.parse::<f64>()};
+        match s.split_once(' ') {
+            Some((re, im)) => Ok(Self::new(part(re)?, part(im)?)),
+            None => Ok(Self::new(part(s)?, 0.0)),
+        }
+    }
+}
+
+/// Arithmetic leaves parts as they come out, infinite or not, for the
+/// checked operations to settle.
+impl ops::Add for Complex {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {Self {re: self.re+rhs.re, im: self.im+rhs.im}}
+}
+
+impl ops::Sub for Complex {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {Self {re: self.re-rhs.re, im: self.im-rhs.im}}
+}
+
+impl ops::Mul for Complex {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Self {re: self.re*rhs.re - self.im*rhs.im, im: self.re*rhs.im + self.im*rhs.re}
+    }
+}
+
+impl ops::Div for Complex {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self {
+        let norm = rhs.re*rhs.re + rhs.im*rhs.im;
+        Self {re: (self.re*rhs.re + self.im*rhs.im)/norm, im: (self.im*rhs.re - self.re*rhs.im)/norm}
+    }
+}
+
+/// There is no remainder of complex numbers; it is NaN.
+impl ops::Rem for Complex {
+    type Output = Self;
+    fn rem(self, _: Self) -> Self {Self::new(f64::NAN, f64::NAN)}
+}
+
+impl ops::Neg for Complex {
+    type Output = Self;
+    fn neg(self) -> Self {Self::new(-self.re, -self.im)}
+}
+
+impl Zero for Complex {
+    fn zero() -> Self {Self::new(0.0, 0.0)}
+    fn is_zero(&self) -> bool {self.re == 0.0 && self.im == 0.0}
+}
+
+impl One for Complex {
+    fn one() -> Self {Self::new(1.0, 0.0)}
+}
+
+impl num_traits::Num for Complex {
+    type FromStrRadixErr = <f64 as num_traits::Num>::FromStrRadixErr;
+    fn from_str_radix(s: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        Ok(Self::new(f64::from_str_radix(s, radix)?, 0.0))
+    }
+}
+
+/// Only complex numbers without an imaginary part have a real value.
+impl ToPrimitive for Complex {
+    fn to_i64(&self) -> Option<i64> {self.real()?.to_i64()}
+    fn to_u64(&self) -> Option<u64> {self.real()?.to_u64()}
+    fn to_i128(&self) -> Option<i128> {self.real()?.to_i128()}
+    fn to_u128(&self) -> Option<u128> {self.real()?.to_u128()}
+    fn to_f64(&self) -> Option<f64> {self.real()}
+}
+
+impl NumCast for Complex {
+    fn from<T: ToPrimitive>(n: T) -> Option<Self> {
+        Some(Self::new(n.to_f64()?, 0.0))
+    }
+}
+
+macro_rules! complex_as {
+    ($($prim:ty),+) => {
+        $(
+            impl AsPrimitive<$prim> for Complex {
+                fn as_(self) -> $prim {self.real().unwrap_or(f64::NAN).as_()}
+            }
+        )+
+    };
+}
+
+complex_as!(i64, f64, usize, u8, i32, f32, i128);
+
+impl CheckedAdd for Complex {
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {(*self + *rhs).checked(&[*self, *rhs])}
+}
+
+impl CheckedSub for Complex {
+    fn checked_sub(&self, rhs: &Self) -> Option<Self> {(*self - *rhs).checked(&[*self, *rhs])}
+}
+
+impl CheckedMul for Complex {
+    fn checked_mul(&self, rhs: &Self) -> Option<Self> {(*self * *rhs).checked(&[*self, *rhs])}
+}
+
+impl CheckedDiv for Complex {
+    fn checked_div(&self, rhs: &Self) -> Option<Self> {(*self / *rhs).checked(&[*self, *rhs])}
+}
+
+impl CheckedRem for Complex {
+    fn checked_rem(&self, _: &Self) -> Option<Self> {None}
+}
+
+/// Whole powers by multiplication, to stay exact where they can,
+/// and any other by logarithm.
+impl CheckedPow for Complex {
+    fn checked_pow(&self, exp: &Self) -> Option<Self> {
+        let power = match exp.real() {
+            Some(exp) if exp.fract() == 0.0 && exp.abs() <= i32::MAX as f64 =>
+                self.powi(exp as i64),
+            Some(exp) if self.is_zero() && exp > 0.0 => Self::zero(),
+            _ => (*exp * self.ln()).exp(),
+        };
+        power.checked(&[*self, *exp])
+    }
+}
+
+impl CheckedNeg for Complex {
+    fn checked_neg(&self) -> Option<Self> {Some(-*self)}
+}
+
+impl CheckedAbs for Complex {
+    fn checked_abs(&self) -> Option<Self> {Self {re: self.norm(), im: 0.0}.checked(&[*self])}
+}
+
+impl Rounding for Complex {
+    const INTEGRAL: bool = false;
+    fn round_down(self) -> Self {self.map(f64::floor)}
+    fn round_up(self) -> Self {self.map(f64::ceil)}
+    fn round_nearest(self) -> Self {self.map(f64::round)}
+    fn round_zero(self) -> Self {self.map(f64::trunc)}
+}
+
+impl NumericPrimitive for Complex {}
+
+impl CastFromFloat for Complex {
+    fn cast(f: f64) -> Option<Self> {
+        f.is_finite().then(|| Self::new(f, 0.0))
+    }
+}
+
+/// A part of each element, as a float.
+pub fn part(number: &Number<Complex>, part: fn(Complex) -> f64) -> Number<f64> {
+    let part = |value: cardinality::Scalar<Complex>| match value {
+        Value(value) if !finite(part(value)).is_nan() => Value(part(value)),
+        _ => NaN,
+    };
+    match number {
+        Scalar(scalar) => Scalar(part(*scalar)),
+        Array(array) => array.to_vec().into_iter().map(part).collect_vec().into(),
+    }
+}
+
+pub fn map(number: &Number<Complex>, f: fn(Complex) -> Complex) -> Number<Complex> {
+    let f = |value: cardinality::Scalar<Complex>| match value {
+        Value(value) => f(value).checked(&[value]).map_or(NaN, Value),
+        NaN => NaN,
+    };
+    match number {
+        Scalar(scalar) => Scalar(f(*scalar)),
+        Array(array) => array.to_vec().into_iter().map(f).collect_vec().into(),
+    }
+}
+
+/// Complex numbers from magnitudes and angles, element-wise if both are
+/// arrays and otherwise spreading a scalar over the other.
+pub fn polar(r: Number<f64>, theta: Number<f64>) -> Result<Number<Complex>, Error> {
+    let polar = |r: cardinality::Scalar<f64>, theta: cardinality::Scalar<f64>| match (r, theta) {
+        (Value(r), Value(theta)) => Complex::from_polar(r, theta).checked(&[]).map_or(NaN, Value),
+        _ => NaN,
+    };
+    match (r, theta) {
+        (Scalar(r), Scalar(theta)) => Ok(Scalar(polar(r, theta))),
+        (Array(r), Scalar(theta)) =>
+            Ok(r.to_vec().into_iter().map(|r| polar(r, theta)).collect_vec().into()),
+        (Scalar(r), Array(theta)) =>
+            Ok(theta.to_vec().into_iter().map(|theta| polar(r, theta)).collect_vec().into()),
+        (Array(r), Array(theta)) => {
+            if r.len() != theta.len() {return Err(Error::LengthMismatch)};
+            Ok(r.to_vec().into_iter().zip(theta.to_vec()).map(|(r, theta)| polar(r, theta)).collect_vec().into())
+        },
+    }
+}
@@ -0,0 +1,195 @@
+use std::cmp::Ordering;
+
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{Zero, Signed, ToPrimitive, NumCast};
+use num_traits::cast::AsPrimitive;
+
+use crate::error::Error;
+use super::{cardinality, policy, Value, NaN};
+use super::primitive::NumericPrimitive;
+
+/// An exact number as worked on: an integer of any size, or a ratio of
+/// them once either operand is one.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Exact {
+    Int(BigInt),
+    Ratio(BigRational),
+}
+
+/// None is NaN, as from a division by zero.
+pub type ExactValue = Option<Exact>;
+
+/// The most bits a power may take, beyond which it is undefined rather
+/// than worked out at length.
+const POW_BITS: u64 = 1 << 24;
+
+/// Wholes become integers and anything else ratios, exactly;
+/// fixed-width primitives only ever hold one or the other.
+pub fn from_primitive<T: NumericPrimitive>(value: T) -> ExactValue {
+    if let Some(int) = value.to_i128() {
+        if <T as NumCast>::from(int) == Some(value) {
+            return Some(Exact::Int(int.into()))
+        }
+    };
+    BigRational::from_float(AsPrimitive::<f64>::as_(value)).map(Exact::Ratio)
+}
+
+/// Through the checked casts, with anything out of range for T, or not
+/// finite as a float, settled by the numeric policy. Ratios become
+/// integers rounded to the nearest, halves away from zero, as floats do.
+pub fn to_scalar<T, V>(value: &Option<V>) -> Result<cardinality::Scalar<T>, Error> where
+    T: NumericPrimitive,
+    V: Clone + Into<Exact>,
+{
+    let Some(value) = value else {return Ok(NaN)};
+    let value: Exact = value.clone().into();
+    let value = if T::INTEGRAL {value.round()} else {value};
+    let cast = match &value {
+        Exact::Int(int)     => <T as NumCast>::from(int.clone()),
+        Exact::Ratio(ratio) => <T as NumCast>::from(ratio.clone()),
+    };
+    match cast {
+        Some(scalar) if AsPrimitive::<f64>::as_(scalar).is_finite() => Ok(Value(scalar)),
+        _ => policy::settle(|| Some(Some(value))),
+    }
+}
+
+impl From<BigInt> for Exact {
+    fn from(item: BigInt) -> Self {Exact::Int(item)}
+}
+
+impl From<BigRational> for Exact {
+    fn from(item: BigRational) -> Self {Exact::Ratio(item)}
+}
+
+fn zip(lhs: &Exact, rhs: &Exact,
+       int: impl FnOnce(&BigInt, &BigInt) -> Option<BigInt>,
+       ratio: impl FnOnce(&BigRational, &BigRational) -> Option<BigRational>)
+    -> ExactValue
+{
+    match (lhs, rhs) {
+        (Exact::Int(lhs), Exact::Int(rhs)) => int(lhs, rhs).map(Exact::Int),
+        _ => ratio(&lhs.ratio(), &rhs.ratio()).map(Exact::Ratio),
+    }
+}
+
+fn map(value: &Exact,
+       int: impl FnOnce(&BigInt) -> BigInt,
+       ratio: impl FnOnce(&BigRational) -> BigRational) -> Exact
+{
+    match value {
+        Exact::Int(value)   => Exact::Int(int(value)),
+        Exact::Ratio(value) => Exact::Ratio(ratio(value)),
+    }
+}
+
+impl Exact {
+    pub fn ratio(&self) -> BigRational {
+        match self {
+            Exact::Int(int)     => BigRational::from_integer(int.clone()),
+            Exact::Ratio(ratio) => ratio.clone(),
+        }
+    }
+
+    /// A ratio truncated to an integer.
+    pub fn int(&self) -> BigInt {
+        match self {
+            Exact::Int(int)     => int.clone(),
+            Exact::Ratio(ratio) => ratio.to_integer(),
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        match self {
+            Exact::Int(int)     => int.is_zero(),
+            Exact::Ratio(ratio) => ratio.is_zero(),
+        }
+    }
+
+    pub fn add(&self, rhs: &Exact) -> ExactValue {
+        zip(self, rhs, |lhs, rhs| Some(lhs + rhs), |lhs, rhs| Some(lhs + rhs))
+    }
+
+    pub fn sub(&self, rhs: &Exact) -> ExactValue {
+        zip(self, rhs, |lhs, rhs| Some(lhs - rhs), |lhs, rhs| Some(lhs - rhs))
+    }
+
+    pub fn mul(&self, rhs: &Exact) -> ExactValue {
+        zip(self, rhs, |lhs, rhs| Some(lhs * rhs), |lhs, rhs| Some(lhs * rhs))
+    }
+
+    /// Integers divide truncating, as fixed-width ones do.
+    pub fn div(&self, rhs: &Exact) -> ExactValue {
+        if rhs.is_zero() {return None};
+        zip(self, rhs, |lhs, rhs| Some(lhs / rhs), |lhs, rhs| Some(lhs / rhs))
+    }
+
+    pub fn rem(&self, rhs: &Exact) -> ExactValue {
+        if rhs.is_zero() {return None};
+        zip(self, rhs, |lhs, rhs| Some(lhs % rhs), |lhs, rhs| Some(lhs % rhs))
+    }
+
+    /// The floored modulus, which takes the sign of the divisor.
+    pub fn modulo(&self, rhs: &Exact) -> ExactValue {
+        let value = self.rem(rhs)?;
+        if !value.is_zero() && (value < Exact::zero()) != (*rhs < Exact::zero()) {
+            value.add(rhs)
+        } else {Some(value)}
+    }
+
+    /// None for an exponent that is not whole, to be worked on as floats;
+    /// negative exponents of integers are NaN, as for fixed-width ones,
+    /// and so are powers of more than POW_BITS bits.
+    pub fn pow(&self, exp: &Exact) -> Option<ExactValue> {
+        let exp = match exp {
+            Exact::Int(exp) => exp.clone(),
+            Exact::Ratio(exp) if exp.is_integer() => exp.to_integer(),
+            Exact::Ratio(_) => return None,
+        };
+        let Some(exp) = exp.to_i32() else {return Some(None)};
+        let bits = match self {
+            Exact::Int(base) => base.bits(),
+            Exact::Ratio(base) => base.numer().bits().max(base.denom().bits()),
+        };
+        if bits.saturating_sub(1)*exp.unsigned_abs() as u64 > POW_BITS {
+            return Some(None)
+        };
+        Some(match self {
+            Exact::Int(_) if exp < 0 => None,
+            Exact::Int(base) => Some(Exact::Int(base.pow(exp.unsigned_abs()))),
+            Exact::Ratio(base) if base.is_zero() && exp < 0 => None,
+            Exact::Ratio(base) => Some(Exact::Ratio(base.pow(exp))),
+        })
+    }
+
+    pub fn min(&self, rhs: &Exact) -> ExactValue {
+        let ratio = !matches!((self, rhs), (Exact::Int(_), Exact::Int(_)));
+        let value = if rhs < self {rhs} else {self};
+        Some(if ratio {Exact::Ratio(value.ratio())} else {value.clone()})
+    }
+
+    pub fn max(&self, rhs: &Exact) -> ExactValue {
+        let ratio = !matches!((self, rhs), (Exact::Int(_), Exact::Int(_)));
+        let value = if rhs > self {rhs} else {self};
+        Some(if ratio {Exact::Ratio(value.ratio())} else {value.clone()})
+    }
+
+    pub fn neg(&self) -> Exact {map(self, |int| -int, |ratio| -ratio)}
+    pub fn abs(&self) -> Exact {map(self, BigInt::abs, BigRational::abs)}
+    pub fn floor(&self) -> Exact {map(self, BigInt::clone, BigRational::floor)}
+    pub fn ceil(&self) -> Exact {map(self, BigInt::clone, BigRational::ceil)}
+    pub fn round(&self) -> Exact {map(self, BigInt::clone, BigRational::round)}
+    pub fn trunc(&self) -> Exact {map(self, BigInt::clone, BigRational::trunc)}
+
+    fn zero() -> Exact {Exact::Int(BigInt::zero())}
+}
+
+impl PartialOrd for Exact {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Exact::Int(lhs), Exact::Int(rhs)) => lhs.partial_cmp(rhs),
+            _ => self.ratio().partial_cmp(&other.ratio()),
+        }
+    }
+}
@@ -0,0 +1,280 @@
+use crate::error::Error;
+use super::{policy, Value, NaN};
+use super::cardinality::Scalar;
+
+/// A dense row-major matrix of floats, NaN elements being f64 NaNs
+/// while it is worked on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix {
+    rows: usize,
+    cols: usize,
+    data: Vec<f64>,
+}
+
+pub fn to_float(scalar: Scalar<f64>) -> f64 {
+    match scalar {
+        Value(value) => value,
+        NaN => f64::NAN,
+    }
+}
+
+/// Anything not finite comes out as NaN, settled by the numeric policy
+/// where the operands were finite, as when a matrix is singular.
+pub fn to_scalar(value: f64, finite: bool) -> Result<Scalar<f64>, Error> {
+    match value {
+        value if value.is_finite() => Ok(Value(value)),
+        value if finite => policy::settle_float(value),
+        _ => Ok(NaN),
+    }
+}
+
+pub fn dot(lhs: &[f64], rhs: &[f64]) -> Result<f64, Error> {
+    if lhs.len() != rhs.len() {return Err(Error::LengthMismatch)};
+    Ok(lhs.iter().zip(rhs).map(|(lhs, rhs)| lhs*rhs).sum())
+}
+
+/// The LU decomposition of a square matrix with partial pivoting:
+/// row i of lu is row perm[i] of the matrix.
+struct Lu {
+    lu: Matrix,
+    perm: Vec<usize>,
+    sign: f64,
+    singular: bool,
+}
+
+impl Matrix {
+    pub fn from_rows(rows: Vec<Vec<f64>>) -> Result<Self, Error> {
+        let cols = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != cols) {
+            return Err(Error::LengthMismatch)
+        };
+        Ok(Self {rows: rows.len(), cols, data: rows.concat()})
+    }
+
+    pub fn column(values: Vec<f64>) -> Self {
+        Self {rows: values.len(), cols: 1, data: values}
+    }
+
+    pub fn outer(lhs: &[f64], rhs: &[f64]) -> Self {
+        let data = lhs.iter().flat_map(|lhs| rhs.iter().map(move |rhs| lhs*rhs)).collect();
+        Self {rows: lhs.len(), cols: rhs.len(), data}
+    }
+
+    fn identity(n: usize) -> Self {
+        let mut data = vec![0.0; n*n];
+        for i in 0..n {
+            data[i*n+i] = 1.0
+        };
+        Self {rows: n, cols: n, data}
+    }
+
+    fn nans(rows: usize, cols: usize) -> Self {
+        Self {rows, cols, data: vec![f64::NAN; rows*cols]}
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn to_rows(&self) -> Vec<Vec<f64>> {
+        if self.cols == 0 {
+            return vec![Vec::new(); self.rows]
+        };
+        self.data.chunks(self.cols).map(<[f64]>::to_vec).collect()
+    }
+
+    fn at(&self, row: usize, col: usize) -> f64 {
+        self.data[row*self.cols+col]
+    }
+
+    fn at_mut(&mut self, row: usize, col: usize) -> &mut f64 {
+        &mut self.data[row*self.cols+col]
+    }
+
+    /// The magnitude below which a pivot is taken for zero, rounding
+    /// error in the elements being of about this size.
+    fn tolerance(&self) -> f64 {
+        let max = self.data.iter().fold(0.0, |max: f64, value| max.max(value.abs()));
+        self.rows.max(self.cols) as f64*f64::EPSILON*max
+    }
+
+    pub fn has_nan(&self) -> bool {
+        self.data.iter().any(|value| !value.is_finite())
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut data = Vec::with_capacity(self.data.len());
+        for col in 0..self.cols {
+            data.extend((0..self.rows).map(|row| self.at(row, col)))
+        };
+        Self {rows: self.cols, cols: self.rows, data}
+    }
+
+    pub fn matmul(&self, other: &Self) -> Result<Self, Error> {
+        if self.cols != other.rows {return Err(Error::LengthMismatch)};
+        let mut product = Self {rows: self.rows, cols: other.cols, data: vec![0.0; self.rows*other.cols]};
+        for row in 0..self.rows {
+            for k in 0..self.cols {
+                let lhs = self.at(row, k);
+                for col in 0..other.cols {
+                    *product.at_mut(row, col) += lhs*other.at(k, col)
+                };
+            };
+        };
+        Ok(product)
+    }
+
+    fn lu(&self) -> Result<Lu, Error> {
+        if self.rows != self.cols {return Err(Error::LengthMismatch)};
+        let n = self.rows;
+        let mut lu = self.clone();
+        let mut perm = (0..n).collect::<Vec<_>>();
+        let mut sign = 1.0;
+        let mut singular = false;
+        let tolerance = self.tolerance();
+
+        for k in 0..n {
+            let pivot = (k..n)
+                .max_by(|&i, &j| lu.at(i, k).abs().total_cmp(&lu.at(j, k).abs()))
+                .unwrap();
+            if lu.at(pivot, k).abs() <= tolerance {
+                singular = true;
+                continue
+            };
+            if pivot != k {
+                for col in 0..n {
+                    lu.data.swap(k*n+col, pivot*n+col)
+                };
+                perm.swap(k, pivot);
+                sign = -sign;
+            };
+            for row in k+1..n {
+                let factor = lu.at(row, k)/lu.at(k, k);
+                *lu.at_mut(row, k) = factor;
+                for col in k+1..n {
+                    let value = lu.at(k, col);
+                    *lu.at_mut(row, col) -= factor*value
+                };
+            };
+        };
+        Ok(Lu {lu, perm, sign, singular})
+    }
+
+    pub fn det(&self) -> Result<f64, Error> {
+        let lu = self.lu()?;
+        if self.has_nan() {return Ok(f64::NAN)};
+        if lu.singular {return Ok(0.0)};
+        Ok((0..self.rows).map(|i| lu.lu.at(i, i)).product::<f64>()*lu.sign)
+    }
+
+    /// x with self x = rhs, each column of rhs a right-hand side;
+    /// all NaN if self is singular.
+    pub fn solve(&self, rhs: &Self) -> Result<Self, Error> {
+        let lu = self.lu()?;
+        if rhs.rows != self.rows {return Err(Error::LengthMismatch)};
+        if lu.singular || self.has_nan() || rhs.has_nan() {
+            return Ok(Self::nans(rhs.rows, rhs.cols))
+        };
+
+        let (n, lu, perm) = (self.rows, lu.lu, lu.perm);
+        let mut x = Self::nans(n, rhs.cols);
+        for col in 0..rhs.cols {
+            let mut y = perm.iter().map(|&row| rhs.at(row, col)).collect::<Vec<_>>();
+            for row in 0..n {
+                y[row] -= (0..row).map(|k| lu.at(row, k)*y[k]).sum::<f64>()
+            };
+            for row in (0..n).rev() {
+                let sum = (row+1..n).map(|k| lu.at(row, k)*y[k]).sum::<f64>();
+                y[row] = (y[row]-sum)/lu.at(row, row)
+            };
+            for (row, value) in y.into_iter().enumerate() {
+                *x.at_mut(row, col) = value
+            };
+        };
+        Ok(x)
+    }
+
+    pub fn inv(&self) -> Result<Self, Error> {
+        self.solve(&Self::identity(self.rows))
+    }
+
+    /// The reduced QR decomposition by Householder reflections: q has
+    /// orthonormal columns and r is upper triangular, as many of each
+    /// as the lesser of rows and columns.
+    pub fn qr(&self) -> (Self, Self) {
+        let (m, n) = (self.rows, self.cols);
+        let k = m.min(n);
+        if self.has_nan() {
+            return (Self::nans(m, k), Self::nans(k, n))
+        };
+
+        let mut r = self.clone();
+        let mut q = Self::identity(m);
+        for j in 0..k {
+            let norm = (j..m).map(|i| r.at(i, j).powi(2)).sum::<f64>().sqrt();
+            if norm == 0.0 {continue};
+            let alpha = if r.at(j, j) > 0.0 {-norm} else {norm};
+            let mut v = (j..m).map(|i| r.at(i, j)).collect::<Vec<_>>();
+            v[0] -= alpha;
+            let vnorm = v.iter().map(|v| v*v).sum::<f64>();
+            if vnorm == 0.0 {continue};
+
+            for col in 0..n {
+                let s = v.iter().enumerate().map(|(i, v)| v*r.at(j+i, col)).sum::<f64>();
+                for (i, v) in v.iter().enumerate() {
+                    *r.at_mut(j+i, col) -= 2.0*s/vnorm*v
+                };
+            };
+            for row in 0..m {
+                let s = v.iter().enumerate().map(|(i, v)| v*q.at(row, j+i)).sum::<f64>();
+                for (i, v) in v.iter().enumerate() {
+                    *q.at_mut(row, j+i) -= 2.0*s/vnorm*v
+                };
+            };
+        };
+
+        let q = Self {
+            rows: m,
+            cols: k,
+            data: (0..m).flat_map(|row| (0..k).map(move |col| (row, col)))
+                .map(|(row, col)| q.at(row, col))
+                .collect(),
+        };
+        let r = Self {
+            rows: k,
+            cols: n,
+            data: (0..k).flat_map(|row| (0..n).map(move |col| (row, col)))
+                .map(|(row, col)| if col < row {0.0} else {r.at(row, col)})
+                .collect(),
+        };
+        (q, r)
+    }
+
+    /// x minimising |self x - rhs| for each column of rhs, by QR; there
+    /// must be at least as many rows as columns, and a matrix without
+    /// full column rank gives NaNs.
+    pub fn lstsq(&self, rhs: &Self) -> Result<Self, Error> {
+        if self.rows < self.cols || rhs.rows != self.rows {
+            return Err(Error::LengthMismatch)
+        };
+        let n = self.cols;
+        let (q, r) = self.qr();
+        let tolerance = self.tolerance();
+        if rhs.has_nan() || (0..n).any(|i| r.at(i, i).abs() <= tolerance) {
+            return Ok(Self::nans(n, rhs.cols))
+        };
+
+        let mut x = q.transpose().matmul(rhs)?;
+        for col in 0..rhs.cols {
+            for row in (0..n).rev() {
+                let sum = (row+1..n).map(|k| r.at(row, k)*x.at(k, col)).sum::<f64>();
+                *x.at_mut(row, col) = (x.at(row, col)-sum)/r.at(row, row)
+            };
+        };
+        Ok(x)
+    }
+}
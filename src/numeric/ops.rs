@@ -1,28 +1,35 @@
+use std::cmp::Ordering;
+
 use crate::error::*;
+use crate::types::bool::{Bool, Truth};
 use super::*;
+use super::exact::{self, Exact, ExactValue};
+use super::policy;
 
 pub trait MonadicOp {
     fn func<T>(scalar: T) ->
         Result<cardinality::Scalar<T>, Error> where
         T: NumericPrimitive + CastFromFloat;
+
+    /// The result on an exact number, or None to work on it as a float.
+    fn exact(_: &Exact) -> Option<ExactValue> {None}
     
     fn target_scalar<T>(scalar: cardinality::Scalar<T>) ->
         Result<cardinality::Scalar<T>, Error> where
         T: NumericPrimitive + CastFromFloat
     {
-        if let Value(value) = scalar {
-            Self::func(value)
-        } else {Ok(NaN)}
+        let Value(value) = scalar else {return Ok(NaN)};
+        match Self::func(value)? {
+            NaN => policy::settle(|| Self::exact(&exact::from_primitive(value)?)),
+            result => Ok(result),
+        }
     }
 
-    fn target_array<T>(mut array: cardinality::Array<T>) ->
+    fn target_array<T>(array: cardinality::Array<T>) ->
         Result<cardinality::Array<T>, Error> where
         T: NumericPrimitive + CastFromFloat
     {
-        for val in array.iter_mut() {
-            *val = Self::target_scalar(*val)?
-        };
-        Ok(array)
+        array.update(|_, val| Self::target_scalar(val))
     }
 
     fn apply<T>(val: Number<T>) ->
@@ -50,10 +57,10 @@ impl<S> MonadicOp for S where
         let Some(value) = Self::float_func(val.as_())? else {
             return Ok(NaN)
         };
-        let Some(value) = CastFromFloat::cast(value) else {
-            return Ok(NaN)
-        };
-        Ok(Value(value))
+        match CastFromFloat::cast(value) {
+            Some(value) => Ok(Value(value)),
+            None => policy::settle_cast(value),
+        }
     }
 }
 
@@ -61,6 +68,9 @@ pub trait DyadicOp {
     fn func<T>(lhs: T, rhs: T) ->
         Result<cardinality::Scalar<T>, Error> where
         T: NumericPrimitive + CastFromFloat;
+
+    /// The result on exact numbers, or None to work on them as floats.
+    fn exact(_: &Exact, _: &Exact) -> Option<ExactValue> {None}
     
     fn operator<C, T, U>(lhs: cardinality::Scalar<T>, rhs: cardinality::Scalar<U>) ->
         Result<cardinality::Scalar<T>, Error> where
@@ -68,15 +78,17 @@ pub trait DyadicOp {
         U: NumericPrimitive,
         C: CasterTrait<T, U>
     {
-        if let (Value(lhs), Value(rhs)) = (lhs, rhs) {
-            let (lhs, rhs) = C::cast(lhs, rhs);
-            let mid = Self::func(lhs, rhs)?;
-            if let Value(mid) = mid {
-                if let Some(mid) = C::back_cast(mid) {
-                    Ok(Value(mid))
-                } else {Ok(NaN)}
-            } else {Ok(NaN)}
-        } else {Ok(NaN)}
+        let (Value(lhs), Value(rhs)) = (lhs, rhs) else {return Ok(NaN)};
+        let (lhs, rhs) = C::cast(lhs, rhs);
+        match Self::func(lhs, rhs)? {
+            Value(mid) => match C::back_cast(mid) {
+                Some(value) => Ok(Value(value)),
+                None => policy::settle_cast(mid),
+            },
+            NaN => policy::settle(|| {
+                Self::exact(&exact::from_primitive(lhs)?, &exact::from_primitive(rhs)?)
+            }),
+        }
     }
 
     fn scalar_scalar<C, T, U>(lhs: cardinality::Scalar<T>, rhs: cardinality::Scalar<U>) ->
@@ -88,29 +100,26 @@ pub trait DyadicOp {
         Self::operator::<C, _, _>(lhs, rhs)
     }
 
-    fn array_array<C, T, U>(mut lhs: cardinality::Array<T>, rhs: cardinality::Array<U>) ->
+    /// Arrays are flat, with no shape to broadcast over, so their
+    /// lengths must match.
+    fn array_array<C, T, U>(lhs: cardinality::Array<T>, rhs: cardinality::Array<U>) ->
         Result<cardinality::Array<T>, Error> where
         T: NumericPrimitive + CastFromFloat,
         U: NumericPrimitive,
         C: CasterTrait<T, U>
    {
         if lhs.len() != rhs.len() {return Err(Error::LengthMismatch)};
-        for (lhs, rhs) in lhs.iter_mut().zip(&rhs) {
-            *lhs = Self::operator::<C, _, _>(*lhs, *rhs)?
-        };
-        Ok(lhs)
+        let rhs = rhs.to_vec();
+        lhs.update(|index, lhs| Self::operator::<C, _, _>(lhs, rhs[index]))
     }
 
-    fn array_scalar<C, T, U>(mut lhs: cardinality::Array<T>, rhs: cardinality::Scalar<U>)
+    fn array_scalar<C, T, U>(lhs: cardinality::Array<T>, rhs: cardinality::Scalar<U>)
         -> Result<cardinality::Array<T>, Error> where
         T: NumericPrimitive + CastFromFloat,
         U: NumericPrimitive,
         C: CasterTrait<T, U>
     {
-        for lhs in &mut lhs {
-            *lhs = Self::operator::<C, _, _>(*lhs, rhs)?
-        };
-        Ok(lhs)
+        lhs.update(|_, lhs| Self::operator::<C, _, _>(lhs, rhs))
     }
 
     fn scalar_array<C, T, U>(mut lhs: cardinality::Scalar<T>, rhs: cardinality::Array<U>)
@@ -119,7 +128,7 @@ pub trait DyadicOp {
         U: NumericPrimitive,
         C: CasterTrait<T, U>
     {
-        for rhs in rhs {
+        for rhs in rhs.to_vec() {
             lhs = Self::operator::<C, _, _>(lhs, rhs)?
         };
         Ok(lhs)
@@ -175,9 +184,51 @@ impl<S> DyadicOp for S where
         let Some(value) = Self::float_func(lhs.as_(), rhs.as_())? else {
             return Ok(NaN)
         };
-        let Some(value) = CastFromFloat::cast(value) else {
-            return Ok(NaN)
-        };
-        Ok(Value(value))
+        match CastFromFloat::cast(value) {
+            Some(value) => Ok(Value(value)),
+            None => policy::settle_cast(value),
+        }
+    }
+}
+
+pub trait CompareOp {
+    fn test(ordering: Ordering) -> bool;
+
+    fn operator<C, T, U>(lhs: cardinality::Scalar<T>, rhs: cardinality::Scalar<U>) -> Truth where
+        T: NumericPrimitive + CastFromFloat,
+        U: NumericPrimitive,
+        C: CasterTrait<T, U>
+    {
+        let (Value(lhs), Value(rhs)) = (lhs, rhs) else {return None};
+        let (lhs, rhs) = C::cast(lhs, rhs);
+        let ordered = |value: &C::Mid| value.partial_cmp(value).is_some();
+        match lhs.partial_cmp(&rhs) {
+            Some(ordering) => Some(Self::test(ordering)),
+            // unequal complex numbers off the real line are unordered, but
+            // not unknown to tests that do not ask which is less
+            None if ordered(&lhs) && ordered(&rhs)
+                && Self::test(Ordering::Less) == Self::test(Ordering::Greater) =>
+                Some(Self::test(Ordering::Less)),
+            None => None,
+        }
+    }
+
+    fn apply<C, T, U>(lhs: Number<T>, rhs: Number<U>) -> Result<Bool, Error> where
+        T: NumericPrimitive + CastFromFloat,
+        U: NumericPrimitive,
+        C: CasterTrait<T, U>
+    {
+        let op = Self::operator::<C, T, U>;
+        match (lhs, rhs) {
+            (Scalar(lhs), Scalar(rhs)) => Ok(op(lhs, rhs).into()),
+            (Array(lhs), Scalar(rhs)) =>
+                Ok(lhs.to_vec().into_iter().map(|lhs| op(lhs, rhs)).collect_vec().into()),
+            (Scalar(lhs), Array(rhs)) =>
+                Ok(rhs.to_vec().into_iter().map(|rhs| op(lhs, rhs)).collect_vec().into()),
+            (Array(lhs), Array(rhs)) => {
+                if lhs.len() != rhs.len() {return Err(Error::LengthMismatch)};
+                Ok(lhs.to_vec().into_iter().zip(rhs.to_vec()).map(|(lhs, rhs)| op(lhs, rhs)).collect_vec().into())
+            },
+        }
     }
 }
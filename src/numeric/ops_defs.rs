@@ -1,8 +1,11 @@
+use std::cmp::Ordering;
+
 use crate::error::*;
 
 use super::*;
 use super::ops::*;
-use super::primitive::NumericPrimitive;
+use super::primitive::{NumericPrimitive, checked_float};
+use super::exact::{Exact, ExactValue};
 
 pub struct NegOp;
 impl MonadicOp for NegOp {
@@ -10,6 +13,8 @@ impl MonadicOp for NegOp {
         T: NumericPrimitive {
         if let Some(value) = val.checked_neg() {Ok(Value(value))} else {Ok(NaN)}
     }
+
+    fn exact(val: &Exact) -> Option<ExactValue> {Some(Some(val.neg()))}
 }
 
 pub struct CosOp;
@@ -23,6 +28,8 @@ impl DyadicOp for AddOp {
         T: NumericPrimitive {
         if let Some(value) = lhs.checked_add(&rhs) {Ok(Value(value))} else {Ok(NaN)}
     }
+
+    fn exact(lhs: &Exact, rhs: &Exact) -> Option<ExactValue> {Some(lhs.add(rhs))}
 }
 
 pub struct SubOp;
@@ -31,6 +38,8 @@ impl DyadicOp for SubOp {
         T: NumericPrimitive {
         if let Some(value) = lhs.checked_sub(&rhs) {Ok(Value(value))} else {Ok(NaN)}
     }
+
+    fn exact(lhs: &Exact, rhs: &Exact) -> Option<ExactValue> {Some(lhs.sub(rhs))}
 }
 
 pub struct MulOp;
@@ -39,6 +48,8 @@ impl DyadicOp for MulOp {
         T: NumericPrimitive {
         if let Some(value) = lhs.checked_mul(&rhs) {Ok(Value(value))} else {Ok(NaN)}
     }
+
+    fn exact(lhs: &Exact, rhs: &Exact) -> Option<ExactValue> {Some(lhs.mul(rhs))}
 }
 
 pub struct DivOp;
@@ -47,4 +58,158 @@ impl DyadicOp for DivOp {
         T: NumericPrimitive {
         if let Some(value) = lhs.checked_div(&rhs) {Ok(Value(value))} else {Ok(NaN)}
     }
+
+    fn exact(lhs: &Exact, rhs: &Exact) -> Option<ExactValue> {Some(lhs.div(rhs))}
+}
+
+macro_rules! compare_ops {
+    ($($op:ident => $test:pat),+) => {
+        $(
+            pub struct $op;
+            impl CompareOp for $op {
+                fn test(ordering: Ordering) -> bool {matches!(ordering, $test)}
+            }
+        )+
+    };
+}
+
+compare_ops!(
+    EqOp => Ordering::Equal,
+    NeOp => Ordering::Less | Ordering::Greater,
+    LtOp => Ordering::Less,
+    LeOp => Ordering::Less | Ordering::Equal,
+    GtOp => Ordering::Greater,
+    GeOp => Ordering::Greater | Ordering::Equal
+);
+
+macro_rules! float_monadic_ops {
+    ($($op:ident => $func:ident),+) => {
+        $(
+            pub struct $op;
+            impl FloatMonadicOp for $op {
+                fn float_func(val: f64) -> Result<Option<f64>, Error> {
+                    Ok(checked_float(&[val], val.$func()))
+                }
+            }
+        )+
+    };
+}
+
+float_monadic_ops!(
+    SinOp   => sin,
+    TanOp   => tan,
+    AsinOp  => asin,
+    AcosOp  => acos,
+    AtanOp  => atan,
+    SinhOp  => sinh,
+    CoshOp  => cosh,
+    TanhOp  => tanh,
+    ExpOp   => exp,
+    LnOp    => ln,
+    Log10Op => log10,
+    Log2Op  => log2,
+    SqrtOp  => sqrt
+);
+
+pub struct AbsOp;
+impl MonadicOp for AbsOp {
+    fn func<T>(val: T) -> Result<NumericValue<T>, Error> where
+        T: NumericPrimitive {
+        if let Some(value) = val.checked_abs() {Ok(Value(value))} else {Ok(NaN)}
+    }
+
+    fn exact(val: &Exact) -> Option<ExactValue> {Some(Some(val.abs()))}
+}
+
+macro_rules! rounding_ops {
+    ($($op:ident => $func:ident $exact:ident),+) => {
+        $(
+            pub struct $op;
+            impl MonadicOp for $op {
+                fn func<T>(val: T) -> Result<NumericValue<T>, Error> where
+                    T: NumericPrimitive {
+                    Ok(Value(val.$func()))
+                }
+
+                fn exact(val: &Exact) -> Option<ExactValue> {Some(Some(val.$exact()))}
+            }
+        )+
+    };
+}
+
+rounding_ops!(
+    FloorOp => round_down floor,
+    CeilOp  => round_up ceil,
+    RoundOp => round_nearest round,
+    TruncOp => round_zero trunc
+);
+
+pub struct Atan2Op;
+impl FloatDyadicOp for Atan2Op {
+    fn float_func(lhs: f64, rhs: f64) -> Result<Option<f64>, Error> {
+        Ok(checked_float(&[lhs, rhs], lhs.atan2(rhs)))
+    }
+}
+
+pub struct PowOp;
+impl DyadicOp for PowOp {
+    fn func<T>(lhs: T, rhs: T) -> Result<cardinality::Scalar<T>, Error> where
+        T: NumericPrimitive {
+        if let Some(value) = lhs.checked_pow(&rhs) {Ok(Value(value))} else {Ok(NaN)}
+    }
+
+    fn exact(lhs: &Exact, rhs: &Exact) -> Option<ExactValue> {lhs.pow(rhs)}
+}
+
+pub struct RemOp;
+impl DyadicOp for RemOp {
+    fn func<T>(lhs: T, rhs: T) -> Result<cardinality::Scalar<T>, Error> where
+        T: NumericPrimitive {
+        if let Some(value) = lhs.checked_rem(&rhs) {Ok(Value(value))} else {Ok(NaN)}
+    }
+
+    fn exact(lhs: &Exact, rhs: &Exact) -> Option<ExactValue> {Some(lhs.rem(rhs))}
+}
+
+/// The floored modulus, which takes the sign of the divisor.
+pub struct ModOp;
+impl DyadicOp for ModOp {
+    fn func<T>(lhs: T, rhs: T) -> Result<cardinality::Scalar<T>, Error> where
+        T: NumericPrimitive {
+        let Some(value) = lhs.checked_rem(&rhs) else {return Ok(NaN)};
+        let zero = T::zero();
+        if value != zero && (value < zero) != (rhs < zero) {
+            Ok(Value(value + rhs))
+        } else {Ok(Value(value))}
+    }
+
+    fn exact(lhs: &Exact, rhs: &Exact) -> Option<ExactValue> {Some(lhs.modulo(rhs))}
+}
+
+pub struct MinOp;
+impl DyadicOp for MinOp {
+    fn func<T>(lhs: T, rhs: T) -> Result<cardinality::Scalar<T>, Error> where
+        T: NumericPrimitive {
+        match lhs.partial_cmp(&rhs) {
+            Some(Ordering::Greater) => Ok(Value(rhs)),
+            Some(_) => Ok(Value(lhs)),
+            None => Ok(NaN),
+        }
+    }
+
+    fn exact(lhs: &Exact, rhs: &Exact) -> Option<ExactValue> {Some(lhs.min(rhs))}
+}
+
+pub struct MaxOp;
+impl DyadicOp for MaxOp {
+    fn func<T>(lhs: T, rhs: T) -> Result<cardinality::Scalar<T>, Error> where
+        T: NumericPrimitive {
+        match lhs.partial_cmp(&rhs) {
+            Some(Ordering::Less) => Ok(Value(rhs)),
+            Some(_) => Ok(Value(lhs)),
+            None => Ok(NaN),
+        }
+    }
+
+    fn exact(lhs: &Exact, rhs: &Exact) -> Option<ExactValue> {Some(lhs.max(rhs))}
 }
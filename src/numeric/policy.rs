@@ -0,0 +1,186 @@
+use std::cell::Cell;
+use std::fmt;
+use std::str::FromStr;
+
+use num_bigint::BigInt;
+use num_traits::{One, Signed, ToPrimitive, NumCast};
+
+use crate::error::Error;
+use super::{cardinality, Value, NaN};
+use super::complex::Complex;
+use super::exact::{self, Exact, ExactValue};
+use super::primitive::NumericPrimitive;
+
+/// How a numeric operation settles a result it has no value for, being
+/// out of range for its type, as on overflow or a cast, or undefined,
+/// as on division by zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Policy {
+    /// The result is NaN.
+    #[default]
+    NaN,
+    /// The operation fails with undefinedresult.
+    Error,
+    /// The result is the value in range nearest to it, or NaN if undefined.
+    Saturate,
+    /// An integer result wraps around as in two's complement, or is NaN if
+    /// undefined; floats, which cannot wrap, saturate.
+    Wrap,
+}
+
+impl Policy {
+    pub fn name(self) -> &'static str {
+        match self {
+            Policy::NaN      => "nan",
+            Policy::Error    => "error",
+            Policy::Saturate => "saturate",
+            Policy::Wrap     => "wrap",
+        }
+    }
+}
+
+impl fmt::Display for Policy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Policy {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "nan"      => Ok(Policy::NaN),
+            "error"    => Ok(Policy::Error),
+            "saturate" => Ok(Policy::Saturate),
+            "wrap"     => Ok(Policy::Wrap),
+            _ => Err(Error::Unknown(s.into())),
+        }
+    }
+}
+
+thread_local! {
+    static POLICY: Cell<Policy> = const {Cell::new(Policy::NaN)};
+}
+
+/// The policy numeric operations on this thread are settled by: that
+/// of the vm running an operator, and otherwise the default.
+pub fn current() -> Policy {
+    POLICY.with(Cell::get)
+}
+
+/// Puts back the policy in force before, when dropped.
+struct Restore(Policy);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        POLICY.with(|cell| cell.set(self.0))
+    }
+}
+
+/// Runs f with numeric operations settled by policy, as a vm runs each
+/// operator by its own, so that it never carries over to another vm
+/// or to numbers worked on outside one.
+pub fn with<R>(policy: Policy, f: impl FnOnce() -> R) -> R {
+    let _restore = Restore(POLICY.with(|cell| cell.replace(policy)));
+    f()
+}
+
+/// Settles a result that came out NaN from operands that were not, by the
+/// current policy. exact gives the result as an exact number, to be fitted
+/// to T: None if the operation has none, Some(None) if it is undefined.
+pub fn settle<T: NumericPrimitive>(exact: impl FnOnce() -> Option<ExactValue>)
+    -> Result<cardinality::Scalar<T>, Error>
+{
+    let fit = match current() {
+        Policy::NaN      => return Ok(NaN),
+        Policy::Error    => return Err(Error::UndefinedResult),
+        Policy::Saturate => T::saturate,
+        Policy::Wrap     => T::wrap,
+    };
+    Ok(exact().flatten().as_ref().and_then(fit).map_or(NaN, Value))
+}
+
+/// Settles a value that is out of range for T, as cast from U.
+pub fn settle_cast<T, U>(value: U) -> Result<cardinality::Scalar<T>, Error> where
+    T: NumericPrimitive,
+    U: NumericPrimitive,
+{
+    settle(|| Some(exact::from_primitive(value)))
+}
+
+/// Settles a float that finite operands gave that is not finite: NaN,
+/// being undefined, or an infinity, having overflowed past the largest
+/// float by as much as it takes to saturate.
+pub fn settle_float(value: f64) -> Result<cardinality::Scalar<f64>, Error> {
+    settle(|| {
+        if value.is_nan() {return Some(None)};
+        Some(exact::from_primitive(value.signum()*f64::MAX)?.mul(&Exact::Int(2.into())))
+    })
+}
+
+/// Fitting exact numbers into a primitive type.
+pub trait Fit: Sized {
+    fn saturate(value: &Exact) -> Option<Self>;
+    fn wrap(value: &Exact) -> Option<Self>;
+}
+
+macro_rules! fit_int {
+    ($($prim:ident),+) => {
+        $(
+            impl Fit for $prim {
+                fn saturate(value: &Exact) -> Option<Self> {
+                    let value = value.round().int();
+                    if value > BigInt::from($prim::MAX) {
+                        Some($prim::MAX)
+                    } else if value < BigInt::from($prim::MIN) {
+                        Some($prim::MIN)
+                    } else {<$prim as NumCast>::from(value)}
+                }
+
+                fn wrap(value: &Exact) -> Option<Self> {
+                    let modulus = BigInt::one() << $prim::BITS;
+                    let value = (value.round().int() % &modulus + &modulus) % &modulus;
+                    if value > BigInt::from($prim::MAX) {
+                        <$prim as NumCast>::from(value - modulus)
+                    } else {<$prim as NumCast>::from(value)}
+                }
+            }
+        )+
+    };
+}
+
+fit_int!(i64, usize, u8, i32, i128);
+
+macro_rules! fit_float {
+    ($($prim:ident),+) => {
+        $(
+            impl Fit for $prim {
+                fn saturate(value: &Exact) -> Option<Self> {
+                    let value = value.ratio();
+                    let infinity = if value.is_negative() {f64::NEG_INFINITY} else {f64::INFINITY};
+                    let value = value.to_f64().unwrap_or(infinity);
+                    Some(value.clamp($prim::MIN.into(), $prim::MAX.into()) as $prim)
+                }
+
+                fn wrap(value: &Exact) -> Option<Self> {Self::saturate(value)}
+            }
+        )+
+    };
+}
+
+fit_float!(f64, f32);
+
+/// Complex numbers have no range to fit into.
+impl Fit for Complex {
+    fn saturate(_: &Exact) -> Option<Self> {None}
+    fn wrap(_: &Exact) -> Option<Self> {None}
+}
+
+/// Settles an exact result, which, having no range, is only ever undefined,
+/// and so NaN, but under the error policy.
+pub fn settle_exact(value: ExactValue) -> Result<ExactValue, Error> {
+    match (value, current()) {
+        (None, Policy::Error) => Err(Error::UndefinedResult),
+        (value, _) => Ok(value),
+    }
+}
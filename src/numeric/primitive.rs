@@ -6,6 +6,8 @@ use num_traits::cast::AsPrimitive;
 use num_traits::ops::checked;
 use num_traits::cast::NumCast;
 
+use super::policy::Fit;
+
 macro_rules! add_checked_dyadic_trait {
     ($trait:ident, $pretrait:ident, $func:ident, $($prim:ty),+) => {
         pub trait $trait: Sized + ops::$pretrait<Self, Output=Self> {
@@ -22,40 +24,94 @@ macro_rules! add_checked_dyadic_trait {
     };
 }
 
-add_checked_dyadic_trait!(CheckedAdd, Add, checked_add, usize, i64, i128);
-add_checked_dyadic_trait!(CheckedSub, Sub, checked_sub, usize, i64, i128);
-add_checked_dyadic_trait!(CheckedMul, Mul, checked_mul, usize, i64, i128);
-add_checked_dyadic_trait!(CheckedDiv, Div, checked_div, usize, i64, i128);
+add_checked_dyadic_trait!(CheckedAdd, Add, checked_add, usize, i64, i128, u8, i32);
+add_checked_dyadic_trait!(CheckedSub, Sub, checked_sub, usize, i64, i128, u8, i32);
+add_checked_dyadic_trait!(CheckedMul, Mul, checked_mul, usize, i64, i128, u8, i32);
+add_checked_dyadic_trait!(CheckedDiv, Div, checked_div, usize, i64, i128, u8, i32);
+add_checked_dyadic_trait!(CheckedRem, Rem, checked_rem, usize, i64, i128, u8, i32);
 
-impl CheckedAdd for f64 {
-    fn checked_add(&self, rhs: &Self) -> Option<Self> {
-        let r = self + rhs;
-        if r.is_finite() {Some(r)} else {None}
-    }
+/// A float result, unless finite operands gave one that is not;
+/// infinite operands may well give an infinite result.
+pub fn checked_float<T: num_traits::Float>(operands: &[T], r: T) -> Option<T> {
+    let overflow = !r.is_finite() && operands.iter().all(|operand| operand.is_finite());
+    if r.is_nan() || overflow {None} else {Some(r)}
 }
 
+macro_rules! add_checked_float {
+    ($($prim:ty),+) => {
+        $(
+            impl CheckedAdd for $prim {
+                fn checked_add(&self, rhs: &Self) -> Option<Self> {
+                    checked_float(&[*self, *rhs], self + rhs)
+                }
+            }
+
+            impl CheckedSub for $prim {
+                fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+                    checked_float(&[*self, *rhs], self - rhs)
+                }
+            }
+
+            impl CheckedMul for $prim {
+                fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+                    checked_float(&[*self, *rhs], self * rhs)
+                }
+            }
+
+            impl CheckedDiv for $prim {
+                fn checked_div(&self, rhs: &Self) -> Option<Self> {
+                    checked_float(&[*self, *rhs], self / rhs)
+                }
+            }
+
+            impl CheckedRem for $prim {
+                fn checked_rem(&self, rhs: &Self) -> Option<Self> {
+                    checked_float(&[*self, *rhs], self % rhs)
+                }
+            }
+
+            impl CheckedPow for $prim {
+                fn checked_pow(&self, exp: &Self) -> Option<Self> {
+                    checked_float(&[*self, *exp], self.powf(*exp))
+                }
+            }
+
+            impl CheckedAbs for $prim {
+                fn checked_abs(&self) -> Option<Self> {Some(self.abs())}
+            }
 
-impl CheckedSub for f64 {
-    fn checked_sub(&self, rhs: &Self) -> Option<Self> {
-        let r = self - rhs;
-        if r.is_finite() {Some(r)} else {None}
-    }
+            impl Rounding for $prim {
+                const INTEGRAL: bool = false;
+                fn round_down(self) -> Self {self.floor()}
+                fn round_up(self) -> Self {self.ceil()}
+                fn round_nearest(self) -> Self {self.round()}
+                fn round_zero(self) -> Self {self.trunc()}
+            }
+        )+
+    };
 }
 
-impl CheckedMul for f64 {
-    fn checked_mul(&self, rhs: &Self) -> Option<Self> {
-        let r = self * rhs;
-        if r.is_finite() {Some(r)} else {None}
-    }
+add_checked_float!(f64, f32);
+
+pub trait CheckedPow: Sized {
+    fn checked_pow(&self, exp: &Self) -> Option<Self>;
 }
 
-impl CheckedDiv for f64 {
-    fn checked_div(&self, rhs: &Self) -> Option<Self> {
-        let r = self / rhs;
-        if r.is_finite() {Some(r)} else {None}
-    }
+macro_rules! add_checked_pow {
+    ($($prim:ty),+) => {
+        $(
+            impl CheckedPow for $prim {
+                fn checked_pow(&self, exp: &Self) -> Option<Self> {
+                    let exp = u32::try_from(*exp).ok()?;
+                    <$prim>::checked_pow(*self, exp)
+                }
+            }
+        )+
+    };
 }
 
+add_checked_pow!(usize, i64, i128, u8, i32);
+
 macro_rules! add_checked_monadic_trait {
     ($trait:ident, $func:ident, $pretrait:ident, $prefunc:ident, $($prim:ty),+) => {
         pub trait $trait: Sized {
@@ -72,20 +128,80 @@ macro_rules! add_checked_monadic_trait {
     };
 }
 
-add_checked_monadic_trait!(CheckedNeg, checked_neg, Neg, neg, i64, f64, i128); 
+add_checked_monadic_trait!(CheckedNeg, checked_neg, Neg, neg, f64, f32);
+
+impl CheckedNeg for i64 {
+    fn checked_neg(&self) -> Option<Self> {i64::checked_neg(*self)}
+}
+
+impl CheckedNeg for i128 {
+    fn checked_neg(&self) -> Option<Self> {i128::checked_neg(*self)}
+}
 
 impl CheckedNeg for usize {
     fn checked_neg(&self) -> Option<Self> {None}
 }
 
+impl CheckedNeg for u8 {
+    fn checked_neg(&self) -> Option<Self> {None}
+}
+
+impl CheckedNeg for i32 {
+    fn checked_neg(&self) -> Option<Self> {i32::checked_neg(*self)}
+}
+
+pub trait CheckedAbs: Sized {
+    fn checked_abs(&self) -> Option<Self>;
+}
+
+impl CheckedAbs for i64 {
+    fn checked_abs(&self) -> Option<Self> {i64::checked_abs(*self)}
+}
+
+impl CheckedAbs for i128 {
+    fn checked_abs(&self) -> Option<Self> {i128::checked_abs(*self)}
+}
+
+impl CheckedAbs for i32 {
+    fn checked_abs(&self) -> Option<Self> {i32::checked_abs(*self)}
+}
+
+impl CheckedAbs for usize {
+    fn checked_abs(&self) -> Option<Self> {Some(*self)}
+}
+
+impl CheckedAbs for u8 {
+    fn checked_abs(&self) -> Option<Self> {Some(*self)}
+}
+
+/// Rounding to an integral value; the identity for integer primitives.
+pub trait Rounding: Sized + Copy {
+    /// Whether every value is integral, as it is for integer primitives.
+    const INTEGRAL: bool = true;
+    fn round_down(self) -> Self {self}
+    fn round_up(self) -> Self {self}
+    fn round_nearest(self) -> Self {self}
+    fn round_zero(self) -> Self {self}
+}
+
+impl Rounding for i64 {}
+impl Rounding for usize {}
+impl Rounding for i128 {}
+impl Rounding for u8 {}
+impl Rounding for i32 {}
+
 pub trait NumericPrimitive:
     Display + Debug + Copy +
     num_traits::Num + CheckedAdd + CheckedSub + CheckedMul + CheckedDiv + CheckedNeg +
+    CheckedRem + CheckedPow + CheckedAbs + Rounding +
     AsPrimitive<f64> + AsPrimitive<i64> + AsPrimitive<usize> + AsPrimitive<i128> +
-    PartialOrd + NumCast
+    PartialOrd + NumCast + Fit
 {}
 
 impl NumericPrimitive for i64 {}
 impl NumericPrimitive for usize {}
 impl NumericPrimitive for f64 {}
 impl NumericPrimitive for i128 {}
+impl NumericPrimitive for u8 {}
+impl NumericPrimitive for i32 {}
+impl NumericPrimitive for f32 {}
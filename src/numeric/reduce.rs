@@ -0,0 +1,239 @@
+use num_traits::cast::AsPrimitive;
+
+use crate::types::num::Num;
+use super::*;
+use super::exact::{self, Exact, ExactValue};
+use super::primitive::checked_float;
+
+/// Reduces a Number to a scalar, either skipping NaN elements or
+/// letting a single NaN make the result NaN.
+pub trait ReduceOp {
+    fn reduce<T>(values: Vec<(usize, T)>) -> Result<Num, Error> where
+        T: NumericPrimitive + CastFromFloat,
+        Num: From<Number<T>>;
+
+    /// The result once a NaN is let through: by default that of no
+    /// values, which is NaN for all but the likes of sum.
+    fn nan<T>() -> Result<Num, Error> where
+        T: NumericPrimitive + CastFromFloat,
+        Num: From<Number<T>>
+    {
+        Self::reduce::<T>(Vec::new())
+    }
+
+    fn apply<T>(number: Number<T>, skip: bool) -> Result<Num, Error> where
+        T: NumericPrimitive + CastFromFloat,
+        Num: From<Number<T>>
+    {
+        let values = match number {
+            Scalar(scalar) => vec![scalar],
+            Array(array)   => array.to_vec(),
+        };
+
+        let mut kept = Vec::with_capacity(values.len());
+        for (index, value) in values.into_iter().enumerate() {
+            match value {
+                Value(value) => kept.push((index, value)),
+                NaN if skip  => (),
+                NaN          => return Self::nan::<T>(),
+            }
+        };
+        Self::reduce(kept)
+    }
+}
+
+fn same_nan<T>() -> Num where
+    T: NumericPrimitive,
+    Num: From<Number<T>>
+{
+    Number::<T>::Scalar(NaN).into()
+}
+
+/// The exact result of folding values with f from init, for the
+/// numeric policy to settle one that came out NaN.
+fn exactly<T: NumericPrimitive>(values: &[(usize, T)], init: Exact,
+                                f: fn(&Exact, &Exact) -> ExactValue) -> Option<ExactValue>
+{
+    Some(values.iter().try_fold(init, |acc, (_, value)| f(&acc, &exact::from_primitive(*value)?)))
+}
+
+/// None, as for no values, is NaN; a result that finite values did not
+/// give finite is settled by the numeric policy.
+fn float<T: NumericPrimitive>(values: &[(usize, T)], value: Option<f64>) -> Result<Num, Error> {
+    let Some(value) = value else {return Ok(Num::Float(Scalar(NaN)))};
+    let operands = values.iter().map(|(_, value)| AsPrimitive::<f64>::as_(*value)).collect::<Vec<_>>();
+    let value = match checked_float(&operands, value) {
+        Some(value) => Value(value),
+        None => policy::settle_float(value)?,
+    };
+    Ok(Num::Float(Scalar(value)))
+}
+
+fn index(value: Option<usize>) -> Num {
+    match value {
+        Some(value) => Num::USize(Scalar(Value(value))),
+        None => Num::USize(Scalar(NaN)),
+    }
+}
+
+fn mean<T: NumericPrimitive>(values: &[(usize, T)]) -> Option<f64> {
+    if values.is_empty() {return None};
+    let sum: f64 = values.iter().map(|(_, value)| AsPrimitive::<f64>::as_(*value)).sum();
+    Some(sum/values.len() as f64)
+}
+
+fn var<T: NumericPrimitive>(values: &[(usize, T)]) -> Option<f64> {
+    let mean = mean(values)?;
+    let sum: f64 = values.iter()
+        .map(|(_, value)| AsPrimitive::<f64>::as_(*value) - mean)
+        .map(|dev| dev*dev)
+        .sum();
+    Some(sum/values.len() as f64)
+}
+
+fn arg<T: NumericPrimitive>(values: Vec<(usize, T)>, better: fn(&T, &T) -> bool)
+    -> Option<usize>
+{
+    values.into_iter()
+        .reduce(|best, next| if better(&next.1, &best.1) {next} else {best})
+        .map(|(index, _)| index)
+}
+
+pub struct SumOp;
+impl ReduceOp for SumOp {
+    fn reduce<T>(values: Vec<(usize, T)>) -> Result<Num, Error> where
+        T: NumericPrimitive + CastFromFloat,
+        Num: From<Number<T>>
+    {
+        let sum = values.iter()
+            .try_fold(T::zero(), |sum, (_, value)| sum.checked_add(value));
+        let sum = match sum {
+            Some(sum) => Value(sum),
+            None => policy::settle(|| exactly(&values, Exact::Int(0.into()), Exact::add))?,
+        };
+        Ok(Number::<T>::Scalar(sum).into())
+    }
+
+    fn nan<T>() -> Result<Num, Error> where T: NumericPrimitive + CastFromFloat, Num: From<Number<T>> {
+        Ok(same_nan::<T>())
+    }
+}
+
+pub struct ProdOp;
+impl ReduceOp for ProdOp {
+    fn reduce<T>(values: Vec<(usize, T)>) -> Result<Num, Error> where
+        T: NumericPrimitive + CastFromFloat,
+        Num: From<Number<T>>
+    {
+        let prod = values.iter()
+            .try_fold(T::one(), |prod, (_, value)| prod.checked_mul(value));
+        let prod = match prod {
+            Some(prod) => Value(prod),
+            None => policy::settle(|| exactly(&values, Exact::Int(1.into()), Exact::mul))?,
+        };
+        Ok(Number::<T>::Scalar(prod).into())
+    }
+
+    fn nan<T>() -> Result<Num, Error> where T: NumericPrimitive + CastFromFloat, Num: From<Number<T>> {
+        Ok(same_nan::<T>())
+    }
+}
+
+pub struct AminOp;
+impl ReduceOp for AminOp {
+    fn reduce<T>(values: Vec<(usize, T)>) -> Result<Num, Error> where
+        T: NumericPrimitive + CastFromFloat,
+        Num: From<Number<T>>
+    {
+        match values.into_iter().map(|(_, value)| value).reduce(|a, b| if b < a {b} else {a}) {
+            Some(min) => Ok(Number::<T>::Scalar(Value(min)).into()),
+            None => Ok(same_nan::<T>()),
+        }
+    }
+}
+
+pub struct AmaxOp;
+impl ReduceOp for AmaxOp {
+    fn reduce<T>(values: Vec<(usize, T)>) -> Result<Num, Error> where
+        T: NumericPrimitive + CastFromFloat,
+        Num: From<Number<T>>
+    {
+        match values.into_iter().map(|(_, value)| value).reduce(|a, b| if b > a {b} else {a}) {
+            Some(max) => Ok(Number::<T>::Scalar(Value(max)).into()),
+            None => Ok(same_nan::<T>()),
+        }
+    }
+}
+
+pub struct MeanOp;
+impl ReduceOp for MeanOp {
+    fn reduce<T>(values: Vec<(usize, T)>) -> Result<Num, Error> where
+        T: NumericPrimitive + CastFromFloat,
+        Num: From<Number<T>>
+    {
+        float(&values, mean(&values))
+    }
+}
+
+/// The population variance.
+pub struct VarOp;
+impl ReduceOp for VarOp {
+    fn reduce<T>(values: Vec<(usize, T)>) -> Result<Num, Error> where
+        T: NumericPrimitive + CastFromFloat,
+        Num: From<Number<T>>
+    {
+        float(&values, var(&values))
+    }
+}
+
+pub struct StdOp;
+impl ReduceOp for StdOp {
+    fn reduce<T>(values: Vec<(usize, T)>) -> Result<Num, Error> where
+        T: NumericPrimitive + CastFromFloat,
+        Num: From<Number<T>>
+    {
+        float(&values, var(&values).map(f64::sqrt))
+    }
+}
+
+pub struct ArgminOp;
+impl ReduceOp for ArgminOp {
+    fn reduce<T>(values: Vec<(usize, T)>) -> Result<Num, Error> where
+        T: NumericPrimitive + CastFromFloat,
+        Num: From<Number<T>>
+    {
+        Ok(index(arg(values, |next, best| next < best)))
+    }
+}
+
+pub struct ArgmaxOp;
+impl ReduceOp for ArgmaxOp {
+    fn reduce<T>(values: Vec<(usize, T)>) -> Result<Num, Error> where
+        T: NumericPrimitive + CastFromFloat,
+        Num: From<Number<T>>
+    {
+        Ok(index(arg(values, |next, best| next > best)))
+    }
+}
+
+/// The number of non-NaN elements, whether or not NaNs are skipped.
+pub struct CountOp;
+impl ReduceOp for CountOp {
+    fn reduce<T>(values: Vec<(usize, T)>) -> Result<Num, Error> where
+        T: NumericPrimitive + CastFromFloat,
+        Num: From<Number<T>>
+    {
+        Ok(index(Some(values.len())))
+    }
+
+    fn apply<T>(number: Number<T>, _: bool) -> Result<Num, Error> where
+        T: NumericPrimitive + CastFromFloat,
+        Num: From<Number<T>>
+    {
+        let count = match number {
+            Scalar(scalar) => usize::from(!scalar.is_nan()),
+            Array(array)   => array.to_vec().into_iter().filter(|value| !value.is_nan()).count(),
+        };
+        Ok(index(Some(count)))
+    }
+}
@@ -0,0 +1,159 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::error::Error;
+use super::cardinality::Scalar;
+use super::primitive::NumericPrimitive;
+use super::policy::{self, Policy};
+
+/// The elements of a numeric array: len elements of a shared buffer,
+/// stride apart from start on. Views taken with range share the buffer,
+/// so a put through any of them is seen through all of them; updates
+/// work in place only on a whole, writable buffer nothing else holds,
+/// and otherwise leave the buffer alone for a new one.
+#[derive(Debug, Clone)]
+pub struct Strided<T: NumericPrimitive> {
+    buffer: Rc<RefCell<Vec<Scalar<T>>>>,
+    start: usize,
+    len: usize,
+    stride: usize,
+    readonly: bool,
+    /// Lent to an operator by the operand stack, which holds the buffer
+    /// too until the operator succeeds, to restore if it fails.
+    lent: bool,
+}
+
+impl<T: NumericPrimitive> From<Vec<Scalar<T>>> for Strided<T> {
+    fn from(values: Vec<Scalar<T>>) -> Self {
+        Self {len: values.len(), buffer: Rc::new(RefCell::new(values)), start: 0, stride: 1, readonly: false, lent: false}
+    }
+}
+
+impl<T: NumericPrimitive> PartialEq for Strided<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.to_vec() == other.to_vec()
+    }
+}
+
+impl<T: NumericPrimitive> Strided<T> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn offset(&self, index: usize) -> usize {
+        self.start + index*self.stride
+    }
+
+    /// Whether both see the same buffer.
+    pub fn shares(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.buffer, &other.buffer)
+    }
+
+    pub fn addr(&self) -> usize {
+        Rc::as_ptr(&self.buffer) as usize
+    }
+
+    pub fn get(&self, index: usize) -> Result<Scalar<T>, Error> {
+        if index >= self.len {
+            return Error::Range {len: self.len, index}.into()
+        };
+        Ok(self.buffer.borrow()[self.offset(index)])
+    }
+
+    pub fn put(&mut self, index: usize, value: Scalar<T>) -> Option<Error> {
+        if self.readonly {
+            return Some(Error::InvalidAccess)
+        };
+        if index >= self.len {
+            return Some(Error::Range {len: self.len, index})
+        };
+        self.buffer.borrow_mut()[self.offset(index)] = value;
+        None
+    }
+
+    /// Copies values over the elements from start on.
+    pub fn put_interval(&mut self, start: usize, values: &[Scalar<T>]) -> Option<Error> {
+        if self.readonly {
+            return Some(Error::InvalidAccess)
+        };
+        if start+values.len() > self.len {
+            return Some(Error::Range {len: self.len, index: start+values.len()})
+        };
+        let buffer = &mut *self.buffer.borrow_mut();
+        for (index, value) in values.iter().enumerate() {
+            buffer[self.start + (start+index)*self.stride] = *value
+        };
+        None
+    }
+
+    /// A view of len elements, stride apart from start on; a stride of
+    /// zero repeats the element at start.
+    pub fn range(&self, start: usize, len: usize, stride: usize) -> Result<Self, Error> {
+        let end = match len.checked_sub(1) {
+            None => Some(start),
+            Some(last) => last.checked_mul(stride)
+                .and_then(|last| last.checked_add(start))
+                .and_then(|last| last.checked_add(1)),
+        };
+        match end {
+            Some(end) if end <= self.len => (),
+            _ => return Error::Range {len: self.len, index: end.unwrap_or(usize::MAX)}.into(),
+        };
+
+        Ok(Self {
+            buffer: self.buffer.clone(),
+            start: self.offset(start.min(self.len)),
+            len,
+            stride: self.stride*stride,
+            readonly: self.readonly,
+            lent: false,
+        })
+    }
+
+    /// The same array through a reference that cannot change it;
+    /// as for lists, other references are unaffected.
+    pub fn readonly(&self) -> Self {
+        Self {readonly: true, ..self.clone()}
+    }
+
+    pub fn writable(&self) -> bool {
+        !self.readonly
+    }
+
+    pub fn lend(&mut self, lent: bool) {
+        self.lent = lent
+    }
+
+    pub fn to_vec(&self) -> Vec<Scalar<T>> {
+        let buffer = self.buffer.borrow();
+        (0..self.len).map(|index| buffer[self.offset(index)]).collect()
+    }
+
+    /// Replaces each element with f of its index and itself. A lent
+    /// buffer is worked on in place only where f cannot fail, as it can
+    /// only under the error policy, leaving the operand stack's copy
+    /// as it was.
+    pub fn update<F>(self, mut f: F) -> Result<Self, Error> where
+        F: FnMut(usize, Scalar<T>) -> Result<Scalar<T>, Error>
+    {
+        let whole = self.start == 0 && self.stride == 1 && self.buffer.borrow().len() == self.len;
+        let holders = if self.lent {2} else {1};
+        let alone = Rc::strong_count(&self.buffer) == holders && Rc::weak_count(&self.buffer) == 0;
+        if whole && alone && !self.readonly && !(self.lent && policy::current() == Policy::Error) {
+            for (index, value) in self.buffer.borrow_mut().iter_mut().enumerate() {
+                *value = f(index, *value)?
+            };
+            return Ok(self)
+        };
+
+        let values = self.to_vec().into_iter()
+            .enumerate()
+            .map(|(index, value)| f(index, value))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(values.into())
+    }
+}
@@ -1,76 +1,28 @@
 use std::str::FromStr;
 use std::convert::From;
-use regex::{Regex, RegexBuilder, Captures, Match};
+use std::io::BufRead;
+use std::rc::Rc;
 
 use crate::vm::{self, *};
 use crate::error::*;
+use crate::lexer::{Lexer, Token, Source};
 use crate::numeric::{Number, NumericValue};
 use crate::types::num::Num;
 use crate::numeric::primitive::NumericPrimitive;
+use crate::numeric::complex::Complex;
+use num_bigint::BigInt;
+use num_rational::BigRational;
 
 pub struct Reader<'a> {
-    regex: Regex,
-    array_f: Regex,
-    array_u: Regex,
-    array_i: Regex,
     vm: &'a mut Vm,
 }
 
-struct RegexStrings {
-    array_f: String,
-    array_u: String,
-    array_i: String,
-    regex: String,
-}
-
-impl RegexStrings {
-    pub fn new() -> Self {
-        let re_f = r"[+\-]?(?:(?:\d+[eE][+\-]?\d+)|(?:(?:\d+[.]\d*)|(?:[.]\d+))(?:[eE][+\-]?\d+)?)";
-        let re_u = r"\d+";
-        let re_i = r"[+\-]?\d+";
-
-        let array_f = format!(r"\s+(?<value>{re_f})d?");
-        let array_u = format!(r"\s+(?<value>{re_u})u?");
-        let array_i = format!(r"\s+(?<value>{re_i})l?");
-        
-        let regex = format!(r"
-^\s*
-(?:
-  (?:(?<float>{re_f})d?)
- |(?:(?<usize>{re_u})u)
- |(?:(?<int>{re_i})l?)
- |(?:<d(?<farray>[^>]*)\s*>)
- |(?:<l(?<iarray>[^>]*)\s*>)
- |(?:<u(?<uarray>[^>]*)\s*>)
- |/(?<pname>[^\s/{{}}\[\]()]+)
- |(?<aname>[^\s/{{}}\[\]()]+)
- |\((?<string>(?:\\\(|[^\)])*)\)
- |(?<pmark>\[)
- |(?<mklist>\])
- |(?<amark>\{{)
- |(?<mkproc>\}})
- |(?<illegal>\S+)
-)\s*
-(?:[|][^\n]*(?:\n|$))?
-");
-        Self {
-            array_f,
-            array_u,
-            array_i,
-            regex,
-        }
-    }
-}
-
-fn mkregex(s: &String) -> Regex {
-    match RegexBuilder::new(s.as_str()).ignore_whitespace(true).build() {
-        Err(err) => panic!("Failed regex: {:?}, '{s}'", err),
-        Ok(regex) => regex,
-    }
-}
-
 trait ParseError: FromStr {
     fn error(err: Self::Err, s: &str) -> Error;
+
+    /// Whether the value read is the one written; floats out of range
+    /// read as infinity, which only `inf` should.
+    fn in_range(&self, _: &str) -> bool {true}
 }
 
 macro_rules! make_parse_error {
@@ -85,91 +37,160 @@ macro_rules! make_parse_error {
     }
 }
 
-make_parse_error!(f64 => Error::FloatParse,
-                  i64 => Error::IntParse,
-                  usize => Error::USizeParse);
+make_parse_error!(i64 => Error::IntParse,
+                  usize => Error::USizeParse,
+                  u8 => Error::IntParse,
+                  i32 => Error::IntParse,
+                  i128 => Error::IntParse,
+                  BigInt => Error::BigIntParse,
+                  BigRational => Error::RationalParse);
 
-fn parse<T: ParseError>(m: Match) -> Result<T, Error> {
-    match m.as_str().parse::<T>() {
-        Ok(v) => Ok(v),
-        Err(e) => Err(<T as ParseError>::error(e, m.as_str())),
+macro_rules! make_float_parse_error {
+    ($($prim:ty),+) => {
+        $(
+            impl ParseError for $prim {
+                fn error(err: Self::Err, s: &str) -> Error {
+                    Error::FloatParse(err, s.to_string())
+                }
+
+                fn in_range(&self, s: &str) -> bool {
+                    self.is_finite() || s.contains("inf")
+                }
+            }
+        )+
     }
 }
 
-fn next_number<'a>(string: &'a str, re: &Regex) ->
-    Result<(&'a str, Match<'a>), Error>
-{
-    let Some(captures) = re.captures(string) else {
-        return Err(Error::Illformed(String::from(string)))
-    };
-    let Some(m) = captures.get(0) else {
-        panic!("Bad capture: {:?}", captures)
-    };
-    let string = &string[m.end()..];
-    let Some(m) = captures.name("value") else {
-        panic!("Bad value capture: {:?}", captures)
-    };
-    Ok((string, m))
+make_float_parse_error!(f64, f32);
+
+impl ParseError for Complex {
+    fn error(err: Self::Err, s: &str) -> Error {
+        Error::FloatParse(err, s.to_string())
+    }
+
+    fn in_range(&self, s: &str) -> bool {
+        s.split(' ').all(|part| part.parse::<f64>().map_or(true, |value| value.in_range(part)))
+    }
 }
 
-fn mkarray<T>(m: Match, re: &Regex) ->
+fn parse<T: ParseError>(s: &str) -> Result<T, Error> {
+    match s.parse::<T>() {
+        Ok(v) if v.in_range(s) => Ok(v),
+        Ok(_) => Err(Error::Illformed(format!("{s} is out of range"))),
+        Err(e) => Err(<T as ParseError>::error(e, s)),
+    }
+}
+
+fn mkarray<T>(elements: Vec<String>) ->
     Result<Frame, Error> where
     T: ParseError + NumericPrimitive,
     Num: From<Number<T>>
 {
-    let mut string = m.as_str();
-    let mut r = Vec::<NumericValue<T>>::new();
-    while string.len() > 0 {
-        let (nstring, m) = next_number(string, re)?;
-        string = nstring;
-        if m.as_str() == "*" {
+    let mut r = Vec::<NumericValue<T>>::with_capacity(elements.len());
+    for element in elements {
+        if element == "*" {
             r.push(NumericValue::<T>::NaN)
         } else {
-            r.push(NumericValue::<T>::Value(parse::<T>(m)?));
+            r.push(NumericValue::<T>::Value(parse::<T>(&element)?));
         }
     };
-    Ok(Frame::Num(Number::<T>::Array(r).into()))
+    Ok(Frame::Num(Number::<T>::from(r).into()))
+}
+
+fn mkexact<T: ParseError>(s: &str) -> Result<Option<T>, Error> {
+    match s {
+        "*" => Ok(None),
+        s => Ok(Some(parse::<T>(s)?)),
+    }
+}
+
+fn mkbools(elements: Vec<String>) -> Frame {
+    let truths = elements.iter().map(|element| match element.as_str() {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    });
+    Frame::Bool(Bool::Array(truths.collect()))
 }
 
-fn mkscalar<T>(m: Match) ->
+fn mkscalar<T>(s: &str) ->
     Result<Frame, Error> where
     T: NumericPrimitive + ParseError,
     Num: From<Number<T>>
 {
-    let value = parse::<T>(m)?;
-    let value = NumericValue::<T>::from_primitive(value);
+    let value = match s {
+        "*" => NumericValue::<T>::NaN,
+        s => NumericValue::<T>::from_primitive(parse::<T>(s)?),
+    };
     Ok(Frame::Num(Number::<T>::Scalar(value).into()))
 }
 
 impl<'a> Reader<'a> {
     pub fn new(vm: &'a mut Vm) -> Self {
-        let strings = RegexStrings::new();
-        Self {
-            regex:   mkregex(&strings.regex),
-            array_f: mkregex(&strings.array_f),
-            array_u: mkregex(&strings.array_u),
-            array_i: mkregex(&strings.array_i),
-            vm,
-        }
+        Self {vm}
     }
 
     pub fn parse(&mut self, string: String) -> Result<Vec<Frame>, Error> {
+        self.read(string.as_bytes(), "<string>")
+    }
+
+    /// Parses everything left in input, reading it a line at a time;
+    /// file names the input in the sources of names and errors.
+    pub fn read<R: BufRead>(&mut self, input: R, file: &str) -> Result<Vec<Frame>, Error> {
+        let file: Rc<str> = file.into();
+        let mut lexer = Lexer::new(input);
         let mut vec = Vec::<Frame>::new();
-        let mut string = string.as_str();
-        while string.len() > 0 {
-            let Some(captures) = self.regex.captures(string) else {
-                return Err(Error::Illformed(String::from(string)))
-            };
-            let Some(m) = captures.get(0) else {
-                panic!("Bad capture: {:?}", captures)
-            };
-            string = &string[m.end()..];
-            vec.push(self.convert(captures)?);
+        while let Some(frame) = self.next(&mut lexer, &file)? {
+            vec.push(frame)
         };
-        
         Ok(vec)
     }
 
+    /// Runs input a statement at a time, each as soon as it has been read,
+    /// so that everything before a syntax error has run when it is met;
+    /// a statement is a token outside of any procedure, or a procedure.
+    pub fn run<R: BufRead>(&mut self, input: R, file: &str) -> Result<Option<Frame>, Error> {
+        let file: Rc<str> = file.into();
+        let mut lexer = Lexer::new(input);
+        let mut statement = Vec::<Frame>::new();
+        let mut depth = 0usize;
+        let mut result = None;
+        while let Some(frame) = self.next(&mut lexer, &file)? {
+            match frame {
+                Frame::Active(Active::Mark) => depth += 1,
+                Frame::Active(Active::EndMark) => depth = depth.saturating_sub(1),
+                _ => (),
+            };
+            statement.push(frame);
+            if depth == 0 {
+                result = self.exec(std::mem::take(&mut statement))?
+            }
+        };
+        if !statement.is_empty() {
+            result = self.exec(statement)?
+        };
+        Ok(result)
+    }
+
+    /// The next frame read from lexer, or None at the end of its input.
+    fn next<R: BufRead>(&mut self, lexer: &mut Lexer<R>, file: &Rc<str>)
+        -> Result<Option<Frame>, Error>
+    {
+        let (position, token) = match lexer.token() {
+            Ok(Some(token)) => token,
+            Ok(None) => return Ok(None),
+            Err(error) => {
+                let source = Source {file: file.clone(), position: lexer.start()};
+                return Err(Error::Read(Box::new(error), source))
+            },
+        };
+        let source = Source {file: file.clone(), position};
+        match self.convert(token, &source) {
+            Ok(frame) => Ok(Some(frame)),
+            Err(error) => Err(Error::Read(Box::new(error), source)),
+        }
+    }
+
     pub fn exec(&mut self, frames: Vec<Frame>) -> Result<Option<Frame>, Error> {
         self.vm.exec(frames)
     }
@@ -185,37 +206,37 @@ impl<'a> Reader<'a> {
         }
     }
 
-    fn convert(&mut self, captures: Captures) -> Result<Frame, Error> {
-        if let Some(m) = captures.name("float") {
-            mkscalar::<f64>(m)
-        } else if let Some(m) = captures.name("int") {
-            mkscalar::<i64>(m)
-        } else if let Some(m) = captures.name("usize") {
-            mkscalar::<usize>(m)
-        } else if let Some(m) = captures.name("farray") {
-            mkarray::<f64>(m, &self.array_f)
-        } else if let Some(m) = captures.name("iarray") {
-            mkarray::<i64>(m, &self.array_i)
-        } else if let Some(m) = captures.name("uarray") {
-            mkarray::<usize>(m, &self.array_u)
-        } else if let Some(_) = captures.name("pmark") {
-            Ok(Passive::Mark.into())
-        } else if let Some(_) = captures.name("mklist") {
-            Ok(vm::ops::MKLIST.into())
-        } else if let Some(_) = captures.name("amark") {
-            Ok(Active::Mark.into())
-        } else if let Some(_) = captures.name("mkproc") {
-            Ok(Active::EndMark.into())
-        } else if let Some(m) = captures.name("pname") {
-            Ok(Passive::Name(self.vm.intern(String::from(m.as_str()))).into())
-        } else if let Some(m) = captures.name("aname") {
-            Ok(Active::Name(self.vm.intern(String::from(m.as_str()))).into())
-        } else if let Some(m) = captures.name("string") {
-            Ok(Passive::String(String::from(m.as_str())).into())
-        } else if let Some(m) = captures.name("illegal") {
-            Err(Error::IllegalSym(m.as_str().into()))
-        } else {
-            panic!("Illegal match {:?}", captures);
+    fn convert(&mut self, token: Token, source: &Source) -> Result<Frame, Error> {
+        match token {
+            Token::Float(s)             => mkscalar::<f64>(&s),
+            Token::Int(s)               => mkscalar::<i64>(&s),
+            Token::USize(s)             => mkscalar::<usize>(&s),
+            Token::Byte(s)              => mkscalar::<u8>(&s),
+            Token::Int32(s)             => mkscalar::<i32>(&s),
+            Token::Float32(s)           => mkscalar::<f32>(&s),
+            Token::Int128(s)            => mkscalar::<i128>(&s),
+            Token::Complex(s)           => mkscalar::<Complex>(&s),
+            Token::BigInt(s)            => Ok(Num::BigInt(mkexact(&s)?).into()),
+            Token::Rational(s)          => Ok(Num::Rational(mkexact(&s)?).into()),
+            Token::FloatArray(elements) => mkarray::<f64>(elements),
+            Token::IntArray(elements)   => mkarray::<i64>(elements),
+            Token::USizeArray(elements) => mkarray::<usize>(elements),
+            Token::ByteArray(elements)  => mkarray::<u8>(elements),
+            Token::Int32Array(elements) => mkarray::<i32>(elements),
+            Token::Float32Array(elements) => mkarray::<f32>(elements),
+            Token::Int128Array(elements) => mkarray::<i128>(elements),
+            Token::ComplexArray(elements) => mkarray::<Complex>(elements),
+            Token::BoolArray(elements)  => Ok(mkbools(elements)),
+            Token::PMark                => Ok(Passive::Mark.into()),
+            Token::MkList               => Ok(vm::ops::MKLIST.into()),
+            Token::AMark                => Ok(Active::Mark.into()),
+            Token::MkProc               => Ok(Active::EndMark.into()),
+            Token::PName(name)          => Ok(Passive::Name(self.vm.intern(name)).into()),
+            Token::AName(name)          => {
+                let source = Some(Rc::new(source.clone()));
+                Ok(Active::Name(self.vm.intern(name), source).into())
+            },
+            Token::String(string)       => Ok(Passive::String(string).into()),
         }
     }
 }
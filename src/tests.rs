@@ -1,11 +1,19 @@
 use crate::vm::*;
 use crate::reader::Reader;
-use crate::numeric::{Value, Scalar};
+use crate::lexer::{Lexer, Position, Token};
+use crate::numeric::{Value, NaN, Scalar, Array};
+use crate::numeric::complex::Complex;
+use num_rational::BigRational;
+use crate::error::Error;
 
 fn int_frame(i: i64) -> Frame {
     Num::Int(Scalar(Value(i))).into()
 }
 
+fn int_array(values: Vec<i64>) -> Frame {
+    Num::Int(values.into_iter().map(Value).collect::<Vec<_>>().into()).into()
+}
+
 #[test]
 fn result() {
     let vm = &mut Vm::new();
@@ -89,3 +97,1249 @@ fn stacked() {
         },
     };
 }
+
+fn eval(vm: &mut Vm, string: &str) -> Result<Option<Frame>, Error> {
+    let mut reader = Reader::new(vm);
+    let frames = reader.parse(String::from(string))?;
+    vm.exec(frames)
+}
+
+#[test]
+fn control() {
+    let vm = &mut Vm::new();
+    let cases = [
+        ("1 { 2 } if", 2),
+        ("0 { 2 } { 3 } ifelse", 3),
+        ("0 5 { 1 add } repeat", 5),
+        ("0 1 2 10 { add } for", 25),
+        ("0 10 -3 0 { add } for", 22),
+        ("0 { 1 add dup 7 sub { } { exit } ifelse } loop", 7),
+        ("0 1 1 1000000 { add dup 10 sub { } { exit } ifelse } for", 10),
+    ];
+    for (string, expected) in cases {
+        match eval(vm, string) {
+            Err(e) => panic!("Error {e:?} in {string}"),
+            Ok(None) => panic!("Empty stack in {string}"),
+            Ok(Some(frame)) => assert_eq!(int_frame(expected), frame, "{string}"),
+        };
+        vm.op_stack.clear();
+    }
+
+    eval(vm, "0 0.5 2 { } for").unwrap();
+    let counters = [0.0, 0.5, 1.0, 1.5, 2.0].map(float_frame);
+    assert_eq!(counters.as_slice(), vm.op_stack.as_slice());
+    vm.op_stack.clear();
+
+    match eval(vm, "exit") {
+        Err(e) if matches!(e.kind(), Error::InvalidExit) => (),
+        other => panic!("Expected InvalidExit: {other:?}"),
+    };
+}
+
+#[test]
+fn compare() {
+    let vm = &mut Vm::new();
+    let cases = [
+        ("1 2 lt", Bool::from(true)),
+        ("2 2.0 eq", Bool::from(true)),
+        ("-1 1u gt", Bool::from(false)),
+        ("1 * le", Bool::from(None)),
+        ("/a /b ne", Bool::from(true)),
+        ("<d 1.0 2.0 3.0> 2 ge", Bool::from(vec![Some(false), Some(true), Some(true)])),
+        ("<l 1 2> <u 2 1> lt", Bool::from(vec![Some(true), Some(false)])),
+        ("true false or false xor not", Bool::from(false)),
+        ("1 * lt false and", Bool::from(false)),
+        ("1 * lt true and", Bool::from(None)),
+    ];
+    for (string, expected) in cases {
+        match eval(vm, string) {
+            Err(e) => panic!("Error {e:?} in {string}"),
+            Ok(None) => panic!("Empty stack in {string}"),
+            Ok(Some(frame)) => assert_eq!(Frame::Bool(expected), frame, "{string}"),
+        };
+        vm.op_stack.clear();
+    }
+
+    match eval(vm, "3 2 gt { 1 } { 2 } ifelse") {
+        Ok(Some(frame)) => assert_eq!(int_frame(1), frame),
+        other => panic!("Unexpected {other:?}"),
+    };
+}
+
+#[test]
+fn dicts() {
+    let vm = &mut Vm::new();
+    let cases = [
+        ("4 dict begin /x 5 def x end", 5),
+        ("4 dict dup begin /y 3 def end /y get", 3),
+        ("2 dict dup 7 exch /z put dup /z known { /z get } { pop 0 } ifelse", 7),
+        ("4 dict begin /w 1 def /w where { pop 2 } { 3 } ifelse end", 2),
+        ("4 dict begin /v 1 def currentdict /v undef /v where { pop 2 } { 3 } ifelse end", 3),
+        ("4 dict begin /x 6 def 4 dict begin /x load end end", 6),
+    ];
+    for (string, expected) in cases {
+        match eval(vm, string) {
+            Err(e) => panic!("Error {e:?} in {string}"),
+            Ok(None) => panic!("Empty stack in {string}"),
+            Ok(Some(frame)) => assert_eq!(int_frame(expected), frame, "{string}"),
+        };
+        vm.op_stack.clear();
+    }
+
+    match eval(vm, "4 dict begin countdictstack end") {
+        Ok(Some(frame)) => assert_eq!(Frame::Num(Num::USize(Scalar(Value(2)))), frame),
+        other => panic!("Unexpected {other:?}"),
+    };
+
+    match eval(vm, "end") {
+        Err(e) if matches!(e.kind(), Error::DictStackUnderflow) => (),
+        other => panic!("Expected DictStackUnderflow: {other:?}"),
+    };
+}
+
+#[test]
+fn save_restore() {
+    let vm = &mut Vm::new();
+    match eval(vm, "save 3 list pop restore 4 dict begin /x 2 def x end") {
+        Ok(Some(frame)) => assert_eq!(int_frame(2), frame),
+        other => panic!("Unexpected {other:?}"),
+    };
+    vm.op_stack.clear();
+
+    match eval(vm, "save 3 list exch restore") {
+        Err(e) if matches!(e.kind(), Error::InvalidRestore) => (),
+        other => panic!("Expected InvalidRestore: {other:?}"),
+    };
+    vm.op_stack.clear();
+
+    match eval(vm, "save 3 dict begin restore") {
+        Err(e) if matches!(e.kind(), Error::InvalidRestore) => (),
+        other => panic!("Expected InvalidRestore: {other:?}"),
+    };
+    vm.op_stack.clear();
+    vm.dict_stack.pop_front();
+
+    let levels = vm.save_stack.len();
+    match eval(vm, "save /x [ 1 2 ] def restore /x where") {
+        Ok(Some(frame)) => assert_eq!(Frame::from(Bool::from(false)), frame),
+        other => panic!("Unexpected {other:?}"),
+    };
+    assert_eq!(levels, vm.save_stack.len());
+    vm.op_stack.clear();
+
+    let cases = [
+        ("/y 1 def save /y 2 def restore y", int_frame(1)),
+        ("/y 1 def save currentdict /y undef restore y", int_frame(1)),
+        ("/a [ 1 2 ] def save [ 3 ] a 0 put restore a 0 get", int_frame(1)),
+        ("/a [ 1 2 ] def save 5 a 0 put save [ 3 ] a 0 put restore a 0 get exch restore",
+         int_frame(5)),
+        ("/a [ 1 2 ] def save 5 a 0 put save 6 a 1 put restore restore a 0 get", int_frame(1)),
+    ];
+    for (program, expected) in cases {
+        match eval(vm, program) {
+            Ok(Some(frame)) => assert_eq!(expected, frame, "{program}"),
+            other => panic!("Unexpected {other:?} in {program}"),
+        };
+        vm.op_stack.clear();
+    }
+}
+
+#[test]
+fn stopped() {
+    let vm = &mut Vm::new();
+    let cases = [
+        ("{ 1 stop 2 } stopped", Bool::from(true).into()),
+        ("{ 1 } stopped", Bool::from(false).into()),
+        ("{ 1 /a add } stopped", Bool::from(true).into()),
+        ("{ { 1 2 3 { stop } repeat } loop } stopped", Bool::from(true).into()),
+        ("{ { stop } stopped pop 4 } stopped pop", int_frame(4)),
+        ("{ pop pop 42 } errordict /optype put 1 /a add", int_frame(42)),
+        ("{ } errordict /optype put 1 /a add", Passive::Name(vm.intern("add".into())).into()),
+    ];
+    for (string, expected) in cases {
+        match eval(vm, string) {
+            Err(e) => panic!("Error {e:?} in {string}"),
+            Ok(None) => panic!("Empty stack in {string}"),
+            Ok(Some(frame)) => assert_eq!(expected, frame, "{string}"),
+        };
+        vm.op_stack.clear();
+    }
+
+    match eval(vm, "1 2 stop") {
+        Err(Error::Stop) => (),
+        other => panic!("Expected Stop: {other:?}"),
+    };
+}
+
+#[test]
+fn recovery() {
+    let vm = &mut Vm::new();
+    match eval(vm, "1 2 /a add 3") {
+        Err(Error::Failed(failure)) => {
+            assert!(matches!(failure.error, Error::OpType));
+            assert_eq!(failure.command, "add");
+            assert_eq!(failure.operands, vec![int_frame(2), Passive::Name(vm.intern("a".into())).into()]);
+        },
+        other => panic!("Expected Failed: {other:?}"),
+    };
+    assert_eq!(vm.op_stack.len(), 3);
+    assert!(vm.exec_stack.is_empty());
+
+    vm.op_stack.clear();
+    match eval(vm, "1 3 { pop } repeat") {
+        Err(e) if matches!(e.kind(), Error::StackUnderflow) => (),
+        other => panic!("Expected StackUnderflow: {other:?}"),
+    };
+    assert!(vm.exec_stack.is_empty());
+
+    vm.op_stack.clear();
+    match eval(vm, "}") {
+        Err(e) if matches!(e.kind(), Error::StackUnderflow) => (),
+        other => panic!("Expected StackUnderflow: {other:?}"),
+    };
+    assert_eq!(vm.proc_depth, 0);
+
+    match eval(vm, "1 2 add") {
+        Ok(Some(frame)) => assert_eq!(int_frame(3), frame),
+        other => panic!("Unexpected {other:?}"),
+    };
+}
+
+fn float_frame(x: f64) -> Frame {
+    Num::Float(Scalar(Value(x))).into()
+}
+
+fn float_array(values: Vec<f64>) -> Frame {
+    Num::Float(values.into_iter().map(Value).collect::<Vec<_>>().into()).into()
+}
+
+#[test]
+fn math() {
+    let vm = &mut Vm::new();
+    let cases = [
+        ("0.0 sin", float_frame(0.0)),
+        ("1.0 exp ln", float_frame(1.0)),
+        ("8.0 log2", float_frame(3.0)),
+        ("16 sqrt", int_frame(4)),
+        ("-2.0 sqrt", Num::Float(Scalar(NaN)).into()),
+        ("0.0 ln", Num::Float(Scalar(NaN)).into()),
+        ("2.0 asin", Num::Float(Scalar(NaN)).into()),
+        ("-7 abs", int_frame(7)),
+        ("-2.5 floor", float_frame(-3.0)),
+        ("-2.5 ceil", float_frame(-2.0)),
+        ("2.5 round", float_frame(3.0)),
+        ("-2.7 trunc", float_frame(-2.0)),
+        ("2 10 pow", int_frame(1024)),
+        ("2 -1 pow", Num::Int(Scalar(NaN)).into()),
+        ("2.0 0.5 pow", float_frame(2f64.sqrt())),
+        ("-7 3 mod", int_frame(2)),
+        ("-7 3 rem", int_frame(-1)),
+        ("7 0 mod", Num::Int(Scalar(NaN)).into()),
+        ("3 5.5 max", int_frame(6)),
+        ("3 -5 min", int_frame(-5)),
+        ("0.0 -1.0 atan2", float_frame(std::f64::consts::PI)),
+        ("<d 1.0 4.0> sqrt", Num::Float(Array(vec![Value(1.0), Value(2.0)].into())).into()),
+        ("<l -1 2> 0 max", Num::Int(Array(vec![Value(0), Value(2)].into())).into()),
+    ];
+    for (string, expected) in cases {
+        match eval(vm, string) {
+            Err(e) => panic!("Error {e:?} in {string}"),
+            Ok(None) => panic!("Empty stack in {string}"),
+            Ok(Some(frame)) => assert_eq!(expected, frame, "{string}"),
+        };
+        vm.op_stack.clear();
+    }
+}
+
+#[test]
+fn reductions() {
+    let vm = &mut Vm::new();
+    let usize_frame = |u: usize| -> Frame {Num::USize(Scalar(Value(u))).into()};
+    let cases = [
+        ("<l 1 2 3 4> sum", int_frame(10)),
+        ("<l 1 0 3 4> <l 1 0 1 1> div sum", Num::Int(Scalar(NaN)).into()),
+        ("<l 1 0 3 4> <l 1 0 1 1> div nansum", int_frame(8)),
+        ("<l 1 2 3 4> prod", int_frame(24)),
+        ("<l 4 -2 7> amin", int_frame(-2)),
+        ("<l 4 -2 7> amax", int_frame(7)),
+        ("<l 1 2 3 4> mean", float_frame(2.5)),
+        ("<d 2.0 4.0 4.0 4.0 5.0 5.0 7.0 9.0> var", float_frame(4.0)),
+        ("<d 2.0 4.0 4.0 4.0 5.0 5.0 7.0 9.0> std", float_frame(2.0)),
+        ("<l 4 -2 7 -2> argmin", usize_frame(1)),
+        ("<l 4 0 7> <l 1 0 1> div argmax", Num::USize(Scalar(NaN)).into()),
+        ("<l 4 0 7> <l 1 0 1> div nanargmax", usize_frame(2)),
+        ("<l 4 0 7> <l 1 0 1> div count", usize_frame(2)),
+        ("<l 9223372036854775807 1> sum", Num::Int(Scalar(NaN)).into()),
+        ("5 sum", int_frame(5)),
+    ];
+    for (string, expected) in cases {
+        match eval(vm, string) {
+            Err(e) => panic!("Error {e:?} in {string}"),
+            Ok(None) => panic!("Empty stack in {string}"),
+            Ok(Some(frame)) => assert_eq!(expected, frame, "{string}"),
+        };
+        vm.op_stack.clear();
+    }
+}
+
+#[test]
+fn conversions() {
+    let vm = &mut Vm::new();
+    let name = |vm: &mut Vm, s: &str| -> Frame {Passive::Name(vm.intern(s.into())).into()};
+    let cases = [
+        ("2.7 cvi", int_frame(3)),
+        ("-2.5 cvi", int_frame(-3)),
+        ("-1 cvu", Num::USize(Scalar(NaN)).into()),
+        ("1e30 cvi", Num::Int(Scalar(NaN)).into()),
+        ("<l 1 -2> cvd", Num::Float(Array(vec![Value(1.0), Value(-2.0)].into())).into()),
+        ("3 /float cvt", float_frame(3.0)),
+        ("3.0 /int cvt 1 add", int_frame(4)),
+        ("<d 1.0> type", name(vm, "floatarray")),
+        ("1u type", name(vm, "usizescalar")),
+        ("2.0 1 type cvt", int_frame(2)),
+        ("<l 1 0> <d 1.0> type cvt", Num::Float(Array(vec![Value(1.0), Value(0.0)].into())).into()),
+        ("1 true type cvt", Bool::from(true).into()),
+    ];
+    for (string, expected) in cases {
+        match eval(vm, string) {
+            Err(e) => panic!("Error {e:?} in {string}"),
+            Ok(None) => panic!("Empty stack in {string}"),
+            Ok(Some(frame)) => assert_eq!(expected, frame, "{string}"),
+        };
+        vm.op_stack.clear();
+    }
+
+    match eval(vm, "3 /bogus cvt") {
+        Err(e) if matches!(e.kind(), Error::Unknown(_)) => (),
+        other => panic!("Expected Unknown: {other:?}"),
+    };
+
+    match eval(vm, "<l 1 2> 1.0 type cvt") {
+        Err(e) if matches!(e.kind(), Error::OpType) => (),
+        other => panic!("Expected OpType: {other:?}"),
+    };
+}
+
+#[test]
+fn introspection() {
+    let vm = &mut Vm::new();
+    let name = |vm: &mut Vm, s: &str| -> Frame {Passive::Name(vm.intern(s.into())).into()};
+    let cases = [
+        ("1 type", name(vm, "intscalar")),
+        ("[ 1 2 ] type", name(vm, "list")),
+        ("{ 1 } type", name(vm, "list")),
+        ("/a type", name(vm, "name")),
+        ("(a) type", name(vm, "string")),
+        ("/add load type", name(vm, "operator")),
+        ("mark type", name(vm, "mark")),
+        ("null type", name(vm, "null")),
+        ("true type", name(vm, "boolscalar")),
+        ("/a xcheck", Bool::from(false).into()),
+        ("{ 1 } xcheck", Bool::from(true).into()),
+        ("/add load xcheck", Bool::from(true).into()),
+        ("1 xcheck", Bool::from(false).into()),
+        ("/a cvx xcheck", Bool::from(true).into()),
+        ("{ 1 } cvlit xcheck", Bool::from(false).into()),
+        ("1 cvx", int_frame(1)),
+        ("1 cvlit", int_frame(1)),
+        ("[ 1 2 add ] cvx exec", int_frame(3)),
+    ];
+    for (string, expected) in cases {
+        match eval(vm, string) {
+            Err(e) => panic!("Error {e:?} in {string}"),
+            Ok(None) => panic!("Empty stack in {string}"),
+            Ok(Some(frame)) => assert_eq!(expected, frame, "{string}"),
+        };
+        vm.op_stack.clear();
+    }
+}
+
+#[test]
+fn lexer() {
+    let input = "1 /a | comment\n  (two\nlines) <l 1\n -2> 2u add";
+    let tokens = Lexer::new(input.as_bytes())
+        .map(|token| token.unwrap())
+        .map(|(Position {line, column}, token)| (line, column, token))
+        .collect::<Vec<_>>();
+    assert_eq!(tokens, vec![
+        (1, 1, Token::Int("1".into())),
+        (1, 3, Token::PName("a".into())),
+        (2, 3, Token::String("two\nlines".into())),
+        (3, 8, Token::IntArray(vec!["1".into(), "-2".into()])),
+        (4, 6, Token::USize("2".into())),
+        (4, 9, Token::AName("add".into())),
+    ]);
+
+    let vm = &mut Vm::new();
+    let cases = [
+        ("{ 1\n2 add\n} exec", int_frame(3)),
+        ("<d 1.0\n2.5d> sum", float_frame(3.5)),
+        ("1.5e1 -2 add", float_frame(13.0)),
+    ];
+    for (string, expected) in cases {
+        match eval(vm, string) {
+            Err(e) => panic!("Error {e:?} in {string}"),
+            Ok(None) => panic!("Empty stack in {string}"),
+            Ok(Some(frame)) => assert_eq!(expected, frame, "{string}"),
+        };
+        vm.op_stack.clear();
+    }
+
+    match Lexer::new("1add".as_bytes()).next() {
+        Some(Ok((_, Token::AName(name)))) => assert_eq!("1add", name),
+        other => panic!("Expected a name: {other:?}"),
+    };
+
+    for string in ["(open", "<l 1 2", "<d 1 2>", "/"] {
+        let mut reader = Reader::new(vm);
+        match reader.parse(String::from(string)) {
+            Err(e) if matches!(e.kind(),
+                Error::Illformed(_) | Error::Unterminated(_) | Error::IllegalSym(_)) => (),
+            other => panic!("Expected a read error in {string}: {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn sources() {
+    let vm = &mut Vm::new();
+    let script = "/f {\n  1 /a add\n} def\n/g { f 2 } def\ng";
+    let frames = Reader::new(vm).read(script.as_bytes(), "test.ps").unwrap();
+    match vm.exec(frames) {
+        Err(Error::Failed(failure)) => {
+            let location = failure.location.as_ref().map(|source| source.to_string());
+            assert_eq!(Some("test.ps:2:8".into()), location);
+            let backtrace = failure.backtrace.iter()
+                .map(|source| source.to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(vec!["test.ps:4:6".to_string()], backtrace);
+        },
+        other => panic!("Expected a failure: {other:?}"),
+    };
+
+    vm.op_stack.clear();
+    let script = "/h {\n  1 /a add\n} bind def\n/k { 2 2 add h } bind def\nk";
+    let frames = Reader::new(vm).read(script.as_bytes(), "test.ps").unwrap();
+    match vm.exec(frames) {
+        Err(Error::Failed(failure)) => {
+            let location = failure.location.as_ref().map(|source| source.to_string());
+            assert_eq!(Some("test.ps:2:8".into()), location);
+        },
+        other => panic!("Expected a failure: {other:?}"),
+    };
+
+    match Reader::new(vm).read("1\n  (open".as_bytes(), "test.ps") {
+        Err(Error::Read(error, source)) => {
+            assert!(matches!(*error, Error::Unterminated(_)));
+            assert_eq!(Position {line: 2, column: 3}, source.position);
+        },
+        other => panic!("Expected a read error: {other:?}"),
+    };
+
+    vm.op_stack.clear();
+    match Reader::new(vm).run("1 2 add\n{ 3 } exec\n(open".as_bytes(), "test.ps") {
+        Err(Error::Read(error, _)) => assert!(matches!(*error, Error::Unterminated(_))),
+        other => panic!("Expected a read error: {other:?}"),
+    };
+    assert_eq!(vec![int_frame(3), int_frame(3)], vm.op_stack);
+    vm.op_stack.clear();
+
+    match eval(vm, "{ a } cvlit 0 get /a cvx eq") {
+        Ok(Some(frame)) => assert_eq!(Frame::from(Bool::from(true)), frame),
+        other => panic!("Unexpected {other:?}"),
+    };
+}
+
+#[test]
+fn continuation() {
+    use crate::lexer::complete;
+    use crate::ext::term::{Prompted, Statements};
+
+    for (string, expected) in [
+        ("1 2 add", true),
+        ("{ 1", false),
+        ("{ 1 } [ 2", false),
+        ("(open", false),
+        ("(a { b) 1", true),
+        ("<d 1.0", false),
+        ("1 | { in a comment", true),
+        ("} 1", true),
+        ("1 /", true),
+    ] {
+        assert_eq!(expected, complete(string.as_bytes()), "{string}");
+    }
+
+    struct Script(Vec<&'static str>);
+    impl Prompted for Script {
+        fn next_line(&mut self, _: &str) -> Option<String> {
+            (!self.0.is_empty()).then(|| self.0.remove(0).into())
+        }
+    }
+
+    let lines = vec!["{ 1", "2 } (a", "b)", "3", "", "[ | ]", "]"];
+    let mut statements = Statements::new(Script(lines));
+    assert_eq!(Some("{ 1\n2 } (a\nb)".into()), statements.next());
+    assert_eq!(Some("3".into()), statements.next());
+    assert_eq!(Some("".into()), statements.next());
+    assert_eq!(Some("[ | ]\n]".into()), statements.next());
+    assert_eq!(None, statements.next());
+}
+
+#[test]
+fn strings() {
+    let vm = &mut Vm::new();
+    let cases = [
+        (r"(a (nested) string)", "a (nested) string"),
+        (r"(tab\tnewline\nparen\)\\)", "tab\tnewline\nparen)\\"),
+        (r"(\101\102\1030)", "ABC0"),
+        (r"(\303\251\u{e9})", "éé"),
+        ("(joined \\\nline)", "joined line"),
+        (r"(\q)", "q"),
+        ("<48 65 6c6C 6f>", "Hello"),
+        ("<4>", "@"),
+        ("<c3 a9>", "é"),
+        ("< c 3 a9>", "é"),
+        ("<>", ""),
+        ("<~87cURD_*#TDfTZ)~>", "Hello, world"),
+        ("<~z~>", "\0\0\0\0"),
+    ];
+    for (string, expected) in cases {
+        let frames = Reader::new(vm).parse(String::from(string));
+        match frames.as_deref() {
+            Ok([Frame::Passive(Passive::String(s))]) => assert_eq!(expected, s, "{string}"),
+            other => panic!("Unexpected {other:?} in {string}"),
+        };
+    }
+
+    let string: Frame = Passive::String("a (b) \\ \n\u{1}".into()).into();
+    assert_eq!(r"(a \(b\) \\ \n\001)", string.to_string());
+    let frames = Reader::new(vm).parse(string.to_string()).unwrap();
+    assert_eq!(vec![string], frames);
+
+    for string in [r"(\377)", "<4g>", "<~abc~", "<~!!!!!!~>", r"(\u{110000})"] {
+        match Reader::new(vm).parse(String::from(string)) {
+            Err(e) if matches!(e.kind(), Error::Illformed(_) | Error::Unterminated(_)) => (),
+            other => panic!("Expected a read error in {string}: {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn literals() {
+    let vm = &mut Vm::new();
+    let cases: [(&str, Frame); 19] = [
+        ("*", Num::Int(Scalar(NaN)).into()),
+        ("*d", Num::Float(Scalar(NaN)).into()),
+        ("*u", Num::USize(Scalar(NaN)).into()),
+        ("<d 1.0 * 3.0d>", Num::Float(Array(vec![Value(1.0), NaN, Value(3.0)].into())).into()),
+        ("<l * 2>", Num::Int(Array(vec![NaN, Value(2)].into())).into()),
+        ("<u 1 *u>", Num::USize(Array(vec![Value(1), NaN].into())).into()),
+        ("16#FF", int_frame(255)),
+        ("-16#ff", int_frame(-255)),
+        ("2#1010u", Num::USize(Scalar(Value(10))).into()),
+        ("0xFF", int_frame(255)),
+        ("0b101l", int_frame(5)),
+        ("16#1d", int_frame(29)),
+        ("1_000_000", int_frame(1_000_000)),
+        ("1_000.25", float_frame(1000.25)),
+        ("<l 0x10 1_0>", Num::Int(Array(vec![Value(16), Value(10)].into())).into()),
+        ("-inf", float_frame(f64::NEG_INFINITY)),
+        ("<d>", Num::Float(Array(vec![].into())).into()),
+        ("<d >", Num::Float(Array(vec![].into())).into()),
+        ("< c3 a9>", Passive::String("é".into()).into()),
+    ];
+    for (string, expected) in cases {
+        match Reader::new(vm).parse(String::from(string)) {
+            Ok(frames) => assert_eq!(vec![expected], frames, "{string}"),
+            Err(e) => panic!("Error {e:?} in {string}"),
+        };
+    }
+
+    for string in ["1__0", "_1", "1_", "37#1", "0x", "2#102"] {
+        match Reader::new(vm).parse(String::from(string)).as_deref() {
+            Ok([Frame::Active(Active::Name(..))]) => (),
+            other => panic!("Expected a name for {string}: {other:?}"),
+        };
+    }
+
+    match Reader::new(vm).parse(String::from("0xFFFFFFFFFFFFFFFFFF")) {
+        Err(e) if matches!(e.kind(), Error::IntParse(..)) => (),
+        other => panic!("Expected IntParse: {other:?}"),
+    };
+
+    for string in ["1e400", "-1e400", "<d 1 1e400>", "1e400+1i"] {
+        match Reader::new(vm).parse(String::from(string)) {
+            Err(e) if matches!(e.kind(), Error::Illformed(_)) => (),
+            other => panic!("Expected Illformed for {string}: {other:?}"),
+        };
+    }
+
+    match eval(vm, "inf 1.0 gt") {
+        Ok(Some(frame)) => assert_eq!(Frame::from(Bool::from(true)), frame),
+        other => panic!("Unexpected {other:?}"),
+    };
+
+    for (program, expected) in [
+        ("inf 1.0 add", f64::INFINITY),
+        ("-inf 2.0 mul", f64::NEG_INFINITY),
+        ("1.0 inf div", 0.0),
+        ("inf exp", f64::INFINITY),
+    ] {
+        match eval(vm, program) {
+            Ok(Some(frame)) => assert_eq!(float_frame(expected), frame, "{program}"),
+            other => panic!("Unexpected {other:?} in {program}"),
+        };
+    }
+    for program in ["1e308 10.0 mul", "1.0 0.0 div", "inf inf sub", "1000.0 exp"] {
+        match eval(vm, program) {
+            Ok(Some(frame)) => assert_eq!(Frame::from(Num::Float(Scalar(NaN))), frame, "{program}"),
+            other => panic!("Unexpected {other:?} in {program}"),
+        };
+    }
+}
+
+#[test]
+fn compiled() {
+    let vm = &mut Vm::new();
+    let cases = [
+        ("{ 1 2 add 3 mul }", int_frame(9)),
+        ("{ 0 1 1 10 { add } for }", int_frame(55)),
+        ("[ true { 1 } { 2 } /ifelse load ] cvx", int_frame(1)),
+        ("[ false { 1 } { 2 } /ifelse load ] cvx", int_frame(2)),
+        ("[ 3 { 1 } { 2 } /ifelse load ] cvx", int_frame(1)),
+        ("[ 7 false { 1 } /if load ] cvx", int_frame(7)),
+        ("{ 0 { 1 add dup 5 ge { exit } if } loop }", int_frame(5)),
+        ("[ 0 [ 1 /add load /dup load 5 /ge load { exit } /if load ] cvx /loop load ] cvx",
+         int_frame(5)),
+        ("{ 3 { 1 /a add } stopped { 4 } if }", int_frame(4)),
+        ("{ { 1 } }", int_frame(1)),
+    ];
+    for (string, expected) in cases {
+        for program in [format!("{string} exec"), format!("{string} compile exec")] {
+            match eval(vm, &program) {
+                Err(e) => panic!("Error {e:?} in {program}"),
+                Ok(None) => panic!("Empty stack in {program}"),
+                Ok(Some(Frame::Active(proc))) => match eval(vm, "exec") {
+                    Ok(Some(frame)) => assert_eq!(expected, frame, "{program} ({proc})"),
+                    other => panic!("Unexpected {other:?} in {program}"),
+                },
+                Ok(Some(frame)) => assert_eq!(expected, frame, "{program}"),
+            };
+            vm.op_stack.clear();
+        }
+    }
+
+    let program = "/fact { dup 1 le { pop 1 } { dup 1 sub fact mul } ifelse } compile def \
+                   /f { 1 } def /g { f } compile def /f { 2 } def 5 fact g add";
+    match eval(vm, program) {
+        Ok(Some(frame)) => assert_eq!(int_frame(122), frame),
+        other => panic!("Unexpected {other:?}"),
+    };
+    vm.op_stack.clear();
+
+    for (program, expected) in [
+        ("/f { 1 2 add } compile def 1 dict begin /add { sub } def f end", int_frame(-1)),
+        ("/f { 1 2 add } bind compile def 1 dict begin /add { sub } def f end", int_frame(3)),
+        ("[ 1 ] dup cvx compile exch 5 exch 0 put mkpass 0 get", int_frame(1)),
+        ("{ 1 } compile mkpass wcheck", Bool::from(false).into()),
+    ] {
+        match eval(vm, program) {
+            Ok(Some(frame)) => assert_eq!(expected, frame, "{program}"),
+            other => panic!("Unexpected {other:?} in {program}"),
+        };
+        vm.op_stack.clear();
+    }
+
+    let name: Frame = Passive::Name(vm.intern("list".into())).into();
+    match eval(vm, "{ 1 } compile dup xcheck exch type") {
+        Ok(Some(_)) => assert_eq!(vec![Bool::from(true).into(), name], vm.op_stack),
+        other => panic!("Unexpected {other:?}"),
+    };
+    vm.op_stack.clear();
+
+    match eval(vm, "[ { 1 } /if load ] cvx compile exec") {
+        Err(Error::Failed(failure)) => {
+            assert!(matches!(failure.error, Error::StackUnderflow));
+            assert_eq!("if", failure.command);
+        },
+        other => panic!("Expected StackUnderflow: {other:?}"),
+    };
+}
+
+#[test]
+fn bind() {
+    let vm = &mut Vm::new();
+    let cases = [
+        ("/f { 1 2 add } bind def /add { sub } def f", int_frame(3)),
+        ("/g { { 2 3 mul } exec } bind def /mul { add } def g", int_frame(6)),
+        ("/h { 4 x } bind def /x { neg } def h", int_frame(-4)),
+        ("[ 0 ] dup dup cvx exch 0 put cvx bind cvlit length", Num::USize(Scalar(Value(1))).into()),
+        ("{ { 1 } } bind cvlit 0 get wcheck", Bool::from(false).into()),
+        ("{ 1 } bind wcheck", Bool::from(true).into()),
+        ("[ 1 ] readonly wcheck", Bool::from(false).into()),
+        ("1 dict readonly wcheck", Bool::from(false).into()),
+        ("[ 1 2 3 ] readonly 1 2 getinterval wcheck", Bool::from(false).into()),
+        ("[ 1 2 ] dup readonly pop dup 5 exch 0 put 0 get", int_frame(5)),
+        ("<l 1 2> readonly wcheck", Bool::from(false).into()),
+        ("<l 1 2 3> readonly 1 2 getinterval wcheck", Bool::from(false).into()),
+        ("<l 1 2> dup readonly pop dup 5 exch 0 put 0 get", int_frame(5)),
+        ("<l 1 2> readonly 1 add wcheck", Bool::from(true).into()),
+        ("{ <l 1 2> } bind exec wcheck", Bool::from(true).into()),
+    ];
+    for (program, expected) in cases {
+        match eval(vm, program) {
+            Ok(Some(frame)) => assert_eq!(expected, frame, "{program}"),
+            other => panic!("Unexpected {other:?} in {program}"),
+        };
+        vm.op_stack.clear();
+    }
+
+    let cases = [
+        "5 [ 1 2 ] readonly 0 put",
+        "2 { { 1 } } bind cvlit 0 get cvlit 0 put",
+        "1 1 dict readonly /a put",
+        "1 dict dup /a 1 put readonly /a undef",
+        "1 dict readonly begin /a 1 def",
+        "5 <l 1 2> readonly 0 put",
+        "<l 5> <l 1 2> readonly 0 putinterval",
+        "1 readonly",
+        "/a wcheck",
+    ];
+    for program in cases {
+        match eval(&mut Vm::new(), program) {
+            Err(e) if matches!(e.kind(), Error::InvalidAccess | Error::OpType) => {},
+            other => panic!("Expected InvalidAccess or OpType: {other:?} in {program}"),
+        };
+    }
+}
+
+#[test]
+fn shared_arrays() {
+    let vm = &mut Vm::new();
+    let name = vm.intern("a".into());
+    match eval(vm, "<l 1 2> dup /a exch def dup [ exch ] 0 get") {
+        Ok(Some(_)) => match (vm.find(name), &vm.op_stack[..]) {
+            (Ok(Frame::Num(Num::Int(Array(stored)))),
+             [Frame::Num(Num::Int(Array(lhs))), Frame::Num(Num::Int(Array(rhs)))]) => {
+                assert!(lhs.shares(rhs));
+                assert!(lhs.shares(&stored));
+            },
+            other => panic!("Unexpected {other:?}"),
+        },
+        other => panic!("Unexpected {other:?}"),
+    };
+    vm.op_stack.clear();
+
+    let cases = [
+        ("a 1 add a", vec![int_array(vec![2, 3]), int_array(vec![1, 2])]),
+        ("a neg a", vec![int_array(vec![-1, -2]), int_array(vec![1, 2])]),
+        ("a dup add", vec![int_array(vec![2, 4])]),
+        ("a a 10 mul add a", vec![int_array(vec![11, 22]), int_array(vec![1, 2])]),
+    ];
+    for (program, expected) in cases {
+        match eval(vm, program) {
+            Ok(Some(_)) => assert_eq!(expected, vm.op_stack, "{program}"),
+            other => panic!("Unexpected {other:?} in {program}"),
+        };
+        vm.op_stack.clear();
+    }
+
+    // an array nothing else holds is updated in place
+    let addr = |frame: &Frame| match frame {
+        Frame::Num(Num::Int(Array(values))) => values.addr(),
+        other => panic!("Unexpected {other:?}"),
+    };
+    vm.op_stack.push(int_array(vec![1, 2, 3]));
+    let before = addr(&vm.op_stack[0]);
+    match eval(vm, "1 add") {
+        Ok(Some(_)) => {
+            assert_eq!(vec![int_array(vec![2, 3, 4])], vm.op_stack);
+            assert_eq!(before, addr(&vm.op_stack[0]));
+        },
+        other => panic!("Unexpected {other:?}"),
+    };
+    vm.op_stack.clear();
+
+    // and left as it was when the operator fails
+    match eval(vm, "<l 1 2> <l 1 2 3> add") {
+        Err(e) => assert_eq!("LengthMismatch", format!("{:?}", e.kind())),
+        other => panic!("Unexpected {other:?}"),
+    };
+    assert_eq!(vec![int_array(vec![1, 2]), int_array(vec![1, 2, 3])], vm.op_stack);
+    vm.op_stack.clear();
+}
+
+#[test]
+fn array_views() {
+    let vm = &mut Vm::new();
+    let cases = [
+        ("<l 1 2 3> 1 get", int_frame(2)),
+        ("<l 1 2 3> length", Num::USize(Scalar(Value(3))).into()),
+        ("<l 1 2 3 4 5> 1 3 getinterval", int_array(vec![2, 3, 4])),
+        ("<l 1 2 3 4 5> 0 3 2 slice", int_array(vec![1, 3, 5])),
+        ("<l 1 2 3 4 5> 4 3 0 slice", int_array(vec![5, 5, 5])),
+        ("<l 1 2 3 4 5 6> 1 3 2 slice 1 2 getinterval", int_array(vec![4, 6])),
+        ("<l 1 2 3 4 5 6> 0 3 2 slice 0 2 2 slice", int_array(vec![1, 5])),
+        ("<l 1 2 3> 1 0 getinterval length", Num::USize(Scalar(Value(0))).into()),
+        ("<l 1 2 3> dup 9 exch 1 put", int_array(vec![1, 9, 3])),
+        ("<l 1 2 3> dup 2.6 exch 0 put", int_array(vec![3, 2, 3])),
+        ("<l 1 2 3 4> dup 1 2 getinterval 9 exch 0 put", int_array(vec![1, 9, 3, 4])),
+        ("<l 1 2 3 4> dup 0 2 2 slice <l 8 9> exch 0 putinterval", int_array(vec![8, 2, 9, 4])),
+        ("<l 1 2 3 4> dup <d 7.0 8.0> exch 2 putinterval", int_array(vec![1, 2, 7, 8])),
+        ("<l 1 2 3 4> dup dup 0 2 getinterval exch 2 putinterval", int_array(vec![1, 2, 1, 2])),
+        ("<l 1 2 3 4> dup 1 2 getinterval 10 mul pop", int_array(vec![1, 2, 3, 4])),
+        ("<l 1 2 3 4> 1 2 getinterval 10 mul", int_array(vec![20, 30])),
+        ("[ 1 2 3 ] dup [ 7 8 ] exch 1 putinterval 2 get", int_frame(8)),
+    ];
+    for (program, expected) in cases {
+        match eval(vm, program) {
+            Ok(Some(frame)) => assert_eq!(expected, frame, "{program}"),
+            other => panic!("Unexpected {other:?} in {program}"),
+        };
+        vm.op_stack.clear();
+    }
+
+    let cases = [
+        "<l 1 2 3> 3 get",
+        "<l 1 2 3> 2 2 getinterval",
+        "<l 1 2 3> 0 2 3 slice",
+        "<l 1 2 3> dup 1 2 getinterval 9 exch 2 put",
+        "<l 1 2> <l 1 2 3> 2 putinterval",
+        "[ 1 2 ] [ 1 2 3 ] 2 putinterval",
+    ];
+    for program in cases {
+        match eval(vm, program) {
+            Err(e) if matches!(e.kind(), Error::Range {..}) => {},
+            other => panic!("Expected Range: {other:?} in {program}"),
+        };
+        vm.op_stack.clear();
+    }
+}
+
+#[test]
+fn array_construction() {
+    let vm = &mut Vm::new();
+    let cases = [
+        ("1 2 4 ramp", int_array(vec![1, 3, 5, 7])),
+        ("0 0.5 3 ramp", float_array(vec![0.0, 0.5, 1.0])),
+        ("5u 1u 2 ramp", Num::USize(vec![Value(5), Value(6)].into()).into()),
+        ("1 1 0 ramp length", Num::USize(Scalar(Value(0))).into()),
+        ("0 1 5 linspace", float_array(vec![0.0, 0.25, 0.5, 0.75, 1.0])),
+        ("2 -2 3 linspace", float_array(vec![2.0, 0.0, -2.0])),
+        ("3 4 1 linspace", float_array(vec![3.0])),
+        ("7 3 fill", int_array(vec![7, 7, 7])),
+        ("2 /float zeros", float_array(vec![0.0, 0.0])),
+        ("2 /int ones", int_array(vec![1, 1])),
+        ("2 /float nans", Num::Float(vec![NaN, NaN].into()).into()),
+        ("<l 1 2> <d 3.0> concat", int_array(vec![1, 2, 3])),
+        ("<l 1 2> 3 concat", int_array(vec![1, 2, 3])),
+        ("[ 1 <l 2 3> <u 4> ] cat", int_array(vec![1, 2, 3, 4])),
+        ("[ <d 1.0> ] cat", float_array(vec![1.0])),
+        ("[ 5 ] cat", int_array(vec![5])),
+        ("<l 1 2 3> reverse", int_array(vec![3, 2, 1])),
+        ("<l 1 2 3 4> 0 2 2 slice reverse", int_array(vec![3, 1])),
+        ("<l 1 2> 2 replicate", int_array(vec![1, 1, 2, 2])),
+        ("<l 1 2> 2 tile", int_array(vec![1, 2, 1, 2])),
+        ("<l 1 2> 0 tile length", Num::USize(Scalar(Value(0))).into()),
+    ];
+    for (program, expected) in cases {
+        match eval(vm, program) {
+            Ok(Some(frame)) => assert_eq!(expected, frame, "{program}"),
+            other => panic!("Unexpected {other:?} in {program}"),
+        };
+        vm.op_stack.clear();
+    }
+
+    let cases = [
+        ("<l 1 2> 1 3 ramp", Error::OpType),
+        ("1n 2n 3 ramp", Error::OpType),
+        ("1 1 -1 ramp", Error::IllNeg),
+        ("2 /quaternion zeros", Error::Unknown("quaternion".into())),
+        ("[ ] cat", Error::OpType),
+        ("[ 1 /a ] cat", Error::OpType),
+    ];
+    for (program, expected) in cases {
+        match eval(vm, program) {
+            Err(e) => assert_eq!(format!("{expected:?}"), format!("{:?}", e.kind()), "{program}"),
+            other => panic!("Expected {expected:?}: {other:?} in {program}"),
+        };
+        vm.op_stack.clear();
+    }
+}
+
+#[test]
+fn linear_algebra() {
+    fn values(frame: Frame) -> Vec<Option<f64>> {
+        match frame {
+            Frame::Num(num) => match num.convert("float") {
+                Ok(Num::Float(Array(array))) => array.to_vec(),
+                Ok(Num::Float(Scalar(scalar))) => vec![scalar],
+                other => panic!("Unexpected {other:?}"),
+            }.into_iter().map(|value| match value {
+                Value(value) => Some(value),
+                NaN => None,
+            }).collect(),
+            Frame::Passive(Passive::List(list)) => (0..list.len().unwrap())
+                .flat_map(|index| values(list.get(index).unwrap()))
+                .collect(),
+            other => panic!("Unexpected {other:?}"),
+        }
+    }
+
+    let vm = &mut Vm::new();
+    let nan = None;
+    let cases = [
+        ("<d 1.0 2.0 3.0> <l 4 5 6> dot", vec![32.0]),
+        ("[ <d 1.0 2.0> <d 3.0 4.0> ] [ <d 5.0 6.0> <d 7.0 8.0> ] matmul", vec![19.0, 22.0, 43.0, 50.0]),
+        ("[ <d 1.0 2.0> <d 3.0 4.0> ] <d 1.0 1.0> matmul", vec![3.0, 7.0]),
+        ("<d 1.0 1.0> [ <d 1.0 2.0> <d 3.0 4.0> ] matmul", vec![4.0, 6.0]),
+        ("<d 1.0 2.0> <d 3.0 4.0> matmul", vec![11.0]),
+        ("<d 1.0 2.0> <d 3.0 4.0 5.0> outer", vec![3.0, 4.0, 5.0, 6.0, 8.0, 10.0]),
+        ("[ <d 1.0 2.0 3.0> <d 4.0 5.0 6.0> ] transpose", vec![1.0, 4.0, 2.0, 5.0, 3.0, 6.0]),
+        ("[ <d 4.0 7.0> <d 2.0 6.0> ] inv", vec![0.6, -0.7, -0.2, 0.4]),
+        ("[ <d 4.0 7.0> <d 2.0 6.0> ] det", vec![10.0]),
+        ("[ <l 1 2> <l 3 4> ] det", vec![-2.0]),
+        ("[ <d 0.0 1.0> <d 1.0 0.0> ] det", vec![-1.0]),
+        ("[ <d 1.0 2.0> <d 2.0 4.0> ] det", vec![0.0]),
+        ("[ <d 2.0 1.0 1.0> <d 1.0 3.0 2.0> <d 1.0 0.0 0.0> ] <d 4.0 5.0 6.0> solve", vec![6.0, 15.0, -23.0]),
+        ("[ <d 1.0 2.0> <d 3.0 4.0> ] [ <d 5.0 6.0> <d 7.0 8.0> ] solve", vec![-3.0, -4.0, 4.0, 5.0]),
+        ("[ <d 1.0 0.0> <d 1.0 1.0> <d 1.0 2.0> ] <d 1.0 2.0 2.0> lstsq", vec![7.0/6.0, 0.5]),
+        ("[ <d 1.0 2.0> <d 3.0 4.0> <d 5.0 6.0> ] qr matmul", vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]),
+        ("[ <d 1.0 2.0> <d 3.0 4.0> <d 5.0 6.0> ] qr pop dup transpose exch matmul", vec![1.0, 0.0, 0.0, 1.0]),
+        ("[ <d 1.0 2.0> <d 3.0 4.0> <d 5.0 6.0> ] qr exch pop 1 get 0 get", vec![0.0]),
+    ];
+    for (program, expected) in cases {
+        match eval(vm, program) {
+            Ok(Some(frame)) => {
+                let values = values(frame);
+                assert_eq!(expected.len(), values.len(), "{program}");
+                for (expected, value) in expected.iter().zip(values) {
+                    assert!(value.is_some_and(|value| (expected-value).abs() < 1e-9),
+                            "{program}: {value:?} for {expected}")
+                }
+            },
+            other => panic!("Unexpected {other:?} in {program}"),
+        };
+        vm.op_stack.clear();
+    }
+
+    let cases = [
+        ("[ <d 1.0 2.0> <d 2.0 4.0> ] inv", vec![nan; 4]),
+        ("[ <d 1. 2. 3.> <d 4. 5. 6.> <d 7. 8. 9.> ] inv", vec![nan; 9]),
+        ("[ <d 1. 2. 3.> <d 4. 5. 6.> <d 7. 8. 9.> ] det", vec![Some(0.0)]),
+        ("[ <d 1. 2. 3.> <d 4. 5. 6.> <d 7. 8. 9.> ] <d 1. 1. 1.> solve", vec![nan; 3]),
+        ("[ <d 1. 2.> <d 2. 4.> <d 3. 6.> ] <d 1. 2. 3.> lstsq", vec![nan; 2]),
+        ("[ <d 1.0 *> <d 3.0 4.0> ] det", vec![nan]),
+        ("[ <d 1.0 *> <d 3.0 4.0> ] <d 1.0 1.0> solve", vec![nan; 2]),
+        ("<d * 1.0> <d 2.0> outer", vec![nan, Some(2.0)]),
+        ("[ <d 1.0 1.0> <d 1.0 1.0> ] <d 1.0 2.0> lstsq", vec![nan; 2]),
+    ];
+    for (program, expected) in cases {
+        match eval(vm, program) {
+            Ok(Some(frame)) => assert_eq!(expected, values(frame), "{program}"),
+            other => panic!("Unexpected {other:?} in {program}"),
+        };
+        vm.op_stack.clear();
+    }
+
+    let cases = [
+        ("[ <d 1.0 2.0> <d 3.0> ] det", Error::LengthMismatch),
+        ("[ <d 1.0 2.0 3.0> <d 4.0 5.0 6.0> ] inv", Error::LengthMismatch),
+        ("<d 1.0 2.0> <d 1.0> dot", Error::LengthMismatch),
+        ("[ <d 1.0 2.0> ] <d 1.0> lstsq", Error::LengthMismatch),
+        ("1 2 dot", Error::OpType),
+        ("[ 1 2 ] det", Error::OpType),
+    ];
+    for (program, expected) in cases {
+        match eval(vm, program) {
+            Err(e) => assert_eq!(format!("{expected:?}"), format!("{:?}", e.kind()), "{program}"),
+            other => panic!("Expected {expected:?}: {other:?} in {program}"),
+        };
+        vm.op_stack.clear();
+    }
+}
+
+#[test]
+fn element_types() {
+    let vm = &mut Vm::new();
+    let cases: [(&str, Frame); 22] = [
+        ("<b 1 2 255>", Num::Byte(vec![Value(1), Value(2), Value(255)].into()).into()),
+        ("<s -1 *>", Num::Int32(vec![Value(-1), NaN].into()).into()),
+        ("<f 1.5 *>", Num::Float32(vec![Value(1.5), NaN].into()).into()),
+        ("<q 170141183460469231731687303715884105727>",
+         Num::Int128(vec![Value(i128::MAX)].into()).into()),
+        ("<t true false *>", Bool::Array(vec![Some(true), Some(false), None]).into()),
+        ("200b 55b add", Num::Byte(Scalar(Value(255))).into()),
+        ("200b 56b add", Num::Byte(Scalar(NaN)).into()),
+        ("2147483647s 1s add", Num::Int32(Scalar(NaN)).into()),
+        ("1.5f 2 add", Num::Float32(Scalar(Value(3.5))).into()),
+        ("<b 1 2> 1 get", Num::Byte(Scalar(Value(2))).into()),
+        ("<l 1 2 3> 1b get", Num::Int(Scalar(Value(2))).into()),
+        ("300 cvb", Num::Byte(Scalar(NaN)).into()),
+        ("2.5 cvf", Num::Float32(Scalar(Value(2.5))).into()),
+        ("1e40 cvf", Num::Float32(Scalar(NaN)).into()),
+        ("inf cvf", Num::Float32(Scalar(Value(f32::INFINITY))).into()),
+        ("-7 cvs", Num::Int32(Scalar(Value(-7))).into()),
+        ("3000000000 cvs", Num::Int32(Scalar(NaN)).into()),
+        ("2.5 cvq", Num::Int128(Scalar(Value(3))).into()),
+        ("<l -1 2> cvq", Num::Int128(vec![Value(-1), Value(2)].into()).into()),
+        ("<t true false *> /byte cvt", Num::Byte(vec![Value(1), Value(0), NaN].into()).into()),
+        ("<l 0 2 *> /bool cvt", Bool::Array(vec![Some(false), Some(true), None]).into()),
+        ("1b 2s lt", Bool::from(true).into()),
+    ];
+    for (program, expected) in cases {
+        match eval(vm, program) {
+            Ok(Some(frame)) => assert_eq!(expected, frame, "{program}"),
+            other => panic!("Unexpected {other:?} in {program}"),
+        };
+        vm.op_stack.clear();
+    }
+
+    let cases = [
+        ("<b 1 2> /int32 cvt", "int32"),
+        ("1.5f /float32 cvt", "float32"),
+        ("1q", "int128"),
+    ];
+    for (program, expected) in cases {
+        match eval(vm, program) {
+            Ok(Some(Frame::Num(num))) => assert_eq!(expected, num.elem_type(), "{program}"),
+            other => panic!("Unexpected {other:?} in {program}"),
+        };
+        vm.op_stack.clear();
+    }
+
+    for program in ["<b 256>", "<t 1>", "<b -1>"] {
+        assert!(eval(vm, program).is_err(), "{program}");
+        vm.op_stack.clear();
+    }
+}
+
+#[test]
+fn complex_numbers() {
+    let vm = &mut Vm::new();
+    let complex = |re: f64, im: f64| -> Frame {
+        Num::Complex(Scalar(Value(Complex::new(re, im)))).into()
+    };
+    let cases = [
+        ("1+2i", complex(1.0, 2.0)),
+        ("-1.5e1-2i", complex(-15.0, -2.0)),
+        ("2i", complex(0.0, 2.0)),
+        ("*+2i", complex(f64::NAN, 2.0)),
+        ("*+*i", Num::Complex(Scalar(NaN)).into()),
+        ("1+2i 3-1i mul", complex(5.0, 5.0)),
+        ("1+2i 3-1i div", complex(0.1, 0.7)),
+        ("2i 2 pow", complex(-4.0, 0.0)),
+        ("2i 1 add", complex(1.0, 2.0)),
+        ("*+2i 1+1i add", complex(f64::NAN, 3.0)),
+        ("1+2i 0 div", Num::Complex(Scalar(NaN)).into()),
+        ("1 2+0i add", int_frame(3)),
+        ("1 2+1i add", Num::Int(Scalar(NaN)).into()),
+        ("1+2i conj", complex(1.0, -2.0)),
+        ("2 conj", int_frame(2)),
+        ("3+4i abs", float_frame(5.0)),
+        ("1+2i re", float_frame(1.0)),
+        ("1+2i im", float_frame(2.0)),
+        ("*+2i re", Num::Float(Scalar(NaN)).into()),
+        ("-1 arg", float_frame(std::f64::consts::PI)),
+        ("2 0 polar", complex(2.0, 0.0)),
+        ("<c 1+2i 3 *>", Num::Complex(vec![Value(Complex::new(1.0, 2.0)), Value(Complex::new(3.0, 0.0)), NaN].into()).into()),
+        ("<c 3+4i 1-1i> 1 get", complex(1.0, -1.0)),
+        ("<c 3+4i *> abs", Num::Float(vec![Value(5.0), NaN].into()).into()),
+        ("<d 1.0 2.0> 0 polar", Num::Complex(vec![Value(Complex::new(1.0, 0.0)), Value(Complex::new(2.0, 0.0))].into()).into()),
+        ("<l 1 2> /complex cvt 1i add", Num::Complex(vec![Value(Complex::new(1.0, 1.0)), Value(Complex::new(2.0, 1.0))].into()).into()),
+        ("1+2i 1+2i eq", Bool::from(true).into()),
+        ("1+2i 1-2i eq", Bool::from(false).into()),
+        ("1+2i 2 ne", Bool::from(true).into()),
+        ("1+2i 1+2i le", Bool::from(true).into()),
+        ("1+2i 2 lt", Bool::Scalar(None).into()),
+        ("2 1+2i ge", Bool::Scalar(None).into()),
+        ("<c 1+0i 1+1i> 2 lt", Bool::Array(vec![Some(true), None]).into()),
+        ("1+2i 2 max", Num::Complex(Scalar(NaN)).into()),
+        ("3+0i 2 min", complex(2.0, 0.0)),
+    ];
+    for (program, expected) in cases {
+        match eval(vm, program) {
+            Ok(Some(frame)) => assert_eq!(expected, frame, "{program}"),
+            other => panic!("Unexpected {other:?} in {program}"),
+        };
+        vm.op_stack.clear();
+    }
+
+    for (value, display) in [(Complex::new(1.0, -2.5), "1-2.5i"), (Complex::new(f64::NAN, 0.0), "*+0i")] {
+        assert_eq!(display, format!("{value}"));
+        match eval(vm, display) {
+            Ok(Some(frame)) => assert_eq!(Frame::from(Num::Complex(Scalar(Value(value)))), frame, "{display}"),
+            other => panic!("Unexpected {other:?} in {display}"),
+        };
+        vm.op_stack.clear();
+    }
+}
+
+#[test]
+fn exact_numbers() {
+    let vm = &mut Vm::new();
+    let big = |value: &str| -> Frame {Num::BigInt(Some(value.parse().unwrap())).into()};
+    let ratio = |numer: i64, denom: i64| -> Frame {
+        Num::Rational(Some(BigRational::new(numer.into(), denom.into()))).into()
+    };
+    let cases = [
+        ("123456789012345678901234567890n", big("123456789012345678901234567890")),
+        ("0xFFn", big("255")),
+        ("-3:4", ratio(-3, 4)),
+        ("9223372036854775807 1 add", Num::Int(Scalar(NaN)).into()),
+        ("9223372036854775807n 1 add", big("9223372036854775808")),
+        ("1 9223372036854775807n add", big("9223372036854775808")),
+        ("2n 100 pow", big("1267650600228229401496703205376")),
+        ("10n 2000000000 pow", Num::BigInt(None).into()),
+        ("-1n 2000000001 pow", big("-1")),
+        ("2:3 -2000000000 pow", Num::Rational(None).into()),
+        ("2n -1 pow", Num::BigInt(None).into()),
+        ("-7n 2 div", big("-3")),
+        ("-7n 2 mod", big("1")),
+        ("1n 0 div", Num::BigInt(None).into()),
+        ("7n 2:1 div", ratio(7, 2)),
+        ("1:3 1:6 add", ratio(1, 2)),
+        ("2:3 -2 pow", ratio(9, 4)),
+        ("-7:2 floor", ratio(-4, 1)),
+        ("-7:2 abs", ratio(7, 2)),
+        ("1:2 0.5 add", float_frame(1.0)),
+        ("4n sqrt", float_frame(2.0)),
+        ("1:3 1:2 min", ratio(1, 3)),
+        ("100000000000000000000n cvi", Num::Int(Scalar(NaN)).into()),
+        ("100n /int cvt", int_frame(100)),
+        ("7:2 /int cvt", int_frame(4)),
+        ("-7:2 /int cvt", int_frame(-4)),
+        ("0.75 /rational cvt", ratio(3, 4)),
+        ("7:2 /bigint cvt", big("4")),
+        ("1:3 1:2 lt", Bool::from(true).into()),
+        ("10n 10 eq", Bool::from(true).into()),
+        ("0n /bool cvt", Bool::from(false).into()),
+    ];
+    for (program, expected) in cases {
+        match eval(vm, program) {
+            Ok(Some(frame)) => assert_eq!(expected, frame, "{program}"),
+            other => panic!("Unexpected {other:?} in {program}"),
+        };
+        vm.op_stack.clear();
+    }
+
+    for (program, display) in [("10n 20 pow", "100000000000000000000n"), ("6:4", "3:2")] {
+        match eval(vm, program) {
+            Ok(Some(frame)) => assert_eq!(display, format!("{frame}"), "{program}"),
+            other => panic!("Unexpected {other:?} in {program}"),
+        };
+        vm.op_stack.clear();
+    }
+
+    let cases = [
+        ("1:0", "rationalparse"),
+        ("<l 1 2> 1n add", "optype"),
+        ("1n sum", "optype"),
+        ("1n 2 fill", "optype"),
+    ];
+    for (program, expected) in cases {
+        match eval(vm, program) {
+            Err(e) => assert_eq!(expected, e.name(), "{program}"),
+            other => panic!("Expected {expected}: {other:?} in {program}"),
+        };
+        vm.op_stack.clear();
+    }
+}
+
+#[test]
+fn numeric_policy() {
+    let vm = &mut Vm::new();
+    let byte = |value: u8| -> Frame {Num::Byte(Scalar(Value(value))).into()};
+    let cases = [
+        ("numericpolicy", Passive::Name(vm.intern("nan".into())).into()),
+        ("9223372036854775807 1 add", Num::Int(Scalar(NaN)).into()),
+        ("/saturate setnumericpolicy 9223372036854775807 1 add", int_frame(i64::MAX)),
+        ("/saturate setnumericpolicy -9223372036854775807 10 mul", int_frame(i64::MIN)),
+        ("/saturate setnumericpolicy 250b 10b add", byte(255)),
+        ("/saturate setnumericpolicy 5b 10b sub", byte(0)),
+        ("/saturate setnumericpolicy 1 1e30 mul", int_frame(i64::MAX)),
+        ("/saturate setnumericpolicy 1e300 1e300 mul", float_frame(f64::MAX)),
+        ("/saturate setnumericpolicy 1 0 div", Num::Int(Scalar(NaN)).into()),
+        ("/saturate setnumericpolicy 1000 cvb", byte(255)),
+        ("/saturate setnumericpolicy 1e40 cvf", Num::Float32(Scalar(Value(f32::MAX))).into()),
+        ("/saturate setnumericpolicy 100000000000000000000n cvi", int_frame(i64::MAX)),
+        ("/wrap setnumericpolicy 9223372036854775807 1 add", int_frame(i64::MIN)),
+        ("/wrap setnumericpolicy 250b 10b add", byte(4)),
+        ("/wrap setnumericpolicy 5b neg", byte(251)),
+        ("/nan setnumericpolicy -9223372036854775807 1 sub neg", Num::Int(Scalar(NaN)).into()),
+        ("/saturate setnumericpolicy -9223372036854775807 1 sub neg", int_frame(i64::MAX)),
+        ("/wrap setnumericpolicy -9223372036854775807 1 sub neg", int_frame(i64::MIN)),
+        ("/wrap setnumericpolicy 1000 cvb", byte(232)),
+        ("/wrap setnumericpolicy <b 200 100> 100b add", Num::Byte(vec![Value(44), Value(200)].into()).into()),
+        ("/wrap setnumericpolicy 1e300 1e300 mul", float_frame(f64::MAX)),
+        ("/nan setnumericpolicy 250b 10b add", Num::Byte(Scalar(NaN)).into()),
+        ("/error setnumericpolicy * 1 add", Num::Int(Scalar(NaN)).into()),
+        ("/nan setnumericpolicy <l 9223372036854775807 1> sum", Num::Int(Scalar(NaN)).into()),
+        ("/saturate setnumericpolicy <l 9223372036854775807 1> sum", int_frame(i64::MAX)),
+        ("/saturate setnumericpolicy <l -9223372036854775807 2> prod", int_frame(i64::MIN)),
+        ("/wrap setnumericpolicy <l 9223372036854775807 1> sum", int_frame(i64::MIN)),
+        ("/saturate setnumericpolicy <d 1e308 1e308> sum", float_frame(f64::MAX)),
+        ("/saturate setnumericpolicy <d 1e308 -1e308 1e308> var", float_frame(f64::MAX)),
+        ("/nan setnumericpolicy 1e308+1i 10 mul", Num::Complex(Scalar(NaN)).into()),
+        ("/error setnumericpolicy [ <d 1.0 *> <d 2.0 4.0> ] det", Num::Float(Scalar(NaN)).into()),
+        ("/nan setnumericpolicy save /wrap setnumericpolicy numericpolicy exch restore",
+         Passive::Name(vm.intern("wrap".into())).into()),
+        ("numericpolicy", Passive::Name(vm.intern("nan".into())).into()),
+        ("1 dict begin /NumericPolicy /saturate def 9223372036854775807 1 add end",
+         int_frame(i64::MAX)),
+        ("1 dict begin /NumericPolicy /wrap def numericpolicy end",
+         Passive::Name(vm.intern("wrap".into())).into()),
+        ("1 dict begin /NumericPolicy /wrap def end 9223372036854775807 1 add",
+         Num::Int(Scalar(NaN)).into()),
+        ("/wrap setnumericpolicy 1 dict begin /NumericPolicy /saturate def 250b 10b add end",
+         byte(255)),
+        ("/nan setnumericpolicy numericpolicy", Passive::Name(vm.intern("nan".into())).into()),
+    ];
+    for (program, expected) in cases {
+        match eval(vm, program) {
+            Ok(Some(frame)) => assert_eq!(expected, frame, "{program}"),
+            other => panic!("Unexpected {other:?} in {program}"),
+        };
+        vm.op_stack.clear();
+    }
+
+    let cases = [
+        ("/error setnumericpolicy 9223372036854775807 1 add", "undefinedresult"),
+        ("/error setnumericpolicy 1 0 div", "undefinedresult"),
+        ("/error setnumericpolicy -1.0 sqrt", "undefinedresult"),
+        ("/error setnumericpolicy 1000 cvb", "undefinedresult"),
+        ("/error setnumericpolicy -9223372036854775807 1 sub neg", "undefinedresult"),
+        ("/error setnumericpolicy 1n 0 div", "undefinedresult"),
+        ("/error setnumericpolicy <l 1 2> 0 div", "undefinedresult"),
+        ("/error setnumericpolicy <l 9223372036854775807 1> sum", "undefinedresult"),
+        ("/error setnumericpolicy <d 1e308 1e308> mean", "undefinedresult"),
+        ("/error setnumericpolicy [ <d 1.0 2.0> <d 2.0 4.0> ] inv", "undefinedresult"),
+        ("/error setnumericpolicy [ <d 1e200 0.0> <d 0.0 1e200> ] det", "undefinedresult"),
+        ("/error setnumericpolicy 1e308+1i 10 mul", "undefinedresult"),
+        ("/clamp setnumericpolicy", "unknown"),
+        ("1 setnumericpolicy", "optype"),
+        ("1 dict begin /NumericPolicy /error def 1 0 div", "undefinedresult"),
+        ("1 dict begin /NumericPolicy /clamp def 1 1 add", "unknown"),
+        ("1 dict begin /NumericPolicy 1 def 1 1 add", "optype"),
+    ];
+    for (program, expected) in cases {
+        match eval(&mut Vm::new(), program) {
+            Err(e) => assert_eq!(expected, e.name(), "{program}"),
+            other => panic!("Expected {expected}: {other:?} in {program}"),
+        };
+        vm.op_stack.clear();
+    }
+
+    // the policy is the vm's, and left behind neither for another vm
+    // nor for numbers worked on outside of one
+    assert!(eval(vm, "/error setnumericpolicy 1 1 add").is_ok());
+    match Num::Int(Scalar(Value(i64::MAX))) + Num::Int(Scalar(Value(1))) {
+        Ok(num) => assert_eq!(Num::Int(Scalar(NaN)), num),
+        other => panic!("Unexpected {other:?}"),
+    };
+    let other = &mut Vm::new();
+    match eval(other, "9223372036854775807 1 add") {
+        Ok(Some(frame)) => assert_eq!(Frame::from(Num::Int(Scalar(NaN))), frame),
+        other => panic!("Unexpected {other:?}"),
+    };
+}
@@ -1,3 +1,4 @@
+pub mod bool;
 pub mod dict;
 pub mod list;
 pub mod name;
@@ -0,0 +1,98 @@
+use std::fmt;
+use std::ops::Not;
+
+use itertools::Itertools;
+
+use crate::error::*;
+
+/// A three-valued truth: None is the boolean NaN (`*`), produced by
+/// comparisons against NaN.
+pub type Truth = Option<bool>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Bool {
+    Scalar(Truth),
+    Array(Vec<Truth>),
+}
+
+impl From<bool> for Bool {
+    fn from(item: bool) -> Self {Bool::Scalar(Some(item))}
+}
+
+impl From<Truth> for Bool {
+    fn from(item: Truth) -> Self {Bool::Scalar(item)}
+}
+
+impl From<Vec<Truth>> for Bool {
+    fn from(item: Vec<Truth>) -> Self {Bool::Array(item)}
+}
+
+fn and(lhs: Truth, rhs: Truth) -> Truth {
+    match (lhs, rhs) {
+        (Some(false), _) | (_, Some(false)) => Some(false),
+        (Some(true), Some(true)) => Some(true),
+        _ => None,
+    }
+}
+
+fn or(lhs: Truth, rhs: Truth) -> Truth {
+    match (lhs, rhs) {
+        (Some(true), _) | (_, Some(true)) => Some(true),
+        (Some(false), Some(false)) => Some(false),
+        _ => None,
+    }
+}
+
+fn xor(lhs: Truth, rhs: Truth) -> Truth {
+    Some(lhs? != rhs?)
+}
+
+impl Bool {
+    /// Combines two booleans element-wise, broadcasting scalars over arrays.
+    pub fn zip_with(lhs: Bool, rhs: Bool, op: fn(Truth, Truth) -> Truth)
+        -> Result<Bool, Error>
+    {
+        match (lhs, rhs) {
+            (Bool::Scalar(lhs), Bool::Scalar(rhs)) => Ok(op(lhs, rhs).into()),
+            (Bool::Array(lhs), Bool::Scalar(rhs)) =>
+                Ok(lhs.into_iter().map(|lhs| op(lhs, rhs)).collect_vec().into()),
+            (Bool::Scalar(lhs), Bool::Array(rhs)) =>
+                Ok(rhs.into_iter().map(|rhs| op(lhs, rhs)).collect_vec().into()),
+            (Bool::Array(lhs), Bool::Array(rhs)) => {
+                if lhs.len() != rhs.len() {return Err(Error::LengthMismatch)};
+                Ok(lhs.into_iter().zip(rhs).map(|(lhs, rhs)| op(lhs, rhs)).collect_vec().into())
+            },
+        }
+    }
+
+    pub fn and(self, rhs: Bool) -> Result<Bool, Error> {Self::zip_with(self, rhs, and)}
+    pub fn or(self, rhs: Bool) -> Result<Bool, Error> {Self::zip_with(self, rhs, or)}
+    pub fn xor(self, rhs: Bool) -> Result<Bool, Error> {Self::zip_with(self, rhs, xor)}
+}
+
+impl Not for Bool {
+    type Output = Bool;
+    fn not(self) -> Self::Output {
+        match self {
+            Bool::Scalar(truth) => truth.map(|t| !t).into(),
+            Bool::Array(array)  => array.into_iter().map(|t| t.map(|t| !t)).collect_vec().into(),
+        }
+    }
+}
+
+fn truth_fmt(truth: &Truth) -> &'static str {
+    match truth {
+        Some(true)  => "true",
+        Some(false) => "false",
+        None        => "*",
+    }
+}
+
+impl fmt::Display for Bool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Bool::Scalar(truth) => f.write_str(truth_fmt(truth)),
+            Bool::Array(array)  => f.write_str(array.iter().map(truth_fmt).join(" ").as_str()),
+        }
+    }
+}
@@ -13,8 +13,10 @@ use crate::vm::Frame;
 use super::name::Name;
 use super::savable::{Saved, Unwrap, HasNew, PENDING};
 
+#[derive(Debug, Clone)]
 pub struct Dict {
     parent: Weak<RefCell<Saved>>,
+    readonly: bool,
 }
 
 impl PartialEq for Dict {
@@ -29,7 +31,7 @@ impl PartialEq for Dict {
 
 impl HasNew for Dict {
     fn new(parent: &Rc<RefCell<Saved>>) -> Self {
-        Self {parent: Rc::<_>::downgrade(parent)}
+        Self {parent: Rc::<_>::downgrade(parent), readonly: false}
     }
     
     fn weak_parent(&self) -> Weak<RefCell<Saved>> {
@@ -73,6 +75,9 @@ impl Dict {
     }
 
     pub fn put(&mut self, name: Name, frame: Frame) -> Option<Error> {
+        if self.readonly {
+            return Some(Error::InvalidAccess)
+        };
         let parent = match self.get_parent() {
             Err(err) => return Some(err),
             Ok(parent) => parent,
@@ -83,6 +88,29 @@ impl Dict {
         dict.insert(name, frame);
         None
     }
+
+    pub fn remove(&mut self, name: &Name) -> Option<Error> {
+        if self.readonly {
+            return Some(Error::InvalidAccess)
+        };
+        let parent = match self.get_parent() {
+            Err(err) => return Some(err),
+            Ok(parent) => parent,
+        };
+
+        let saved = &mut *parent.borrow_mut();
+        Unwrap::<HashMap<Name, Frame>>::unwrap_mut(saved).remove(name);
+        None
+    }
+
+    /// The same dict through a reference that cannot change it.
+    pub fn readonly(&self) -> Self {
+        Self {readonly: true, ..self.clone()}
+    }
+
+    pub fn writable(&self) -> bool {
+        !self.readonly
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
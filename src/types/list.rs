@@ -15,6 +15,7 @@ pub struct List {
     parent: Weak<RefCell<Saved>>,
     start: usize,
     len: usize,
+    readonly: bool,
 }
 
 impl PartialEq for List {
@@ -33,7 +34,7 @@ impl HasNew for List {
     fn new(parent: &Rc<RefCell<Saved>>) -> Self {
         let saved = &*parent.borrow();
         let list: &Vec<_> = saved.unwrap();
-        Self {parent: Rc::<_>::downgrade(parent), start: 0, len: list.len()}
+        Self {parent: Rc::<_>::downgrade(parent), start: 0, len: list.len(), readonly: false}
     }
 
     fn weak_parent(&self) -> Weak<RefCell<Saved>> {
@@ -59,6 +60,9 @@ impl List {
     }
 
     pub fn put(&mut self, index: usize, frame: Frame) -> Option<Error> {
+        if self.readonly {
+            return Some(Error::InvalidAccess)
+        };
         if index >= self.len {
             return Some(Error::Range {len: self.len, index})
         };
@@ -74,6 +78,16 @@ impl List {
         None
     }
 
+    /// The element just before this list in its parent: for the rest of
+    /// a procedure on the execution stack, the last one executed.
+    pub fn previous(&self) -> Option<Frame> {
+        if self.start == 0 {return None};
+        let parent = self.parent.upgrade()?;
+        let saved = &*parent.borrow();
+        let list: &Vec<_> = saved.unwrap();
+        list.get(self.start-1).cloned()
+    }
+
     pub fn range(&self, start: usize, len: usize) -> Result<Self, Error> {
         if start+len > self.len {
             return (Error::Range {len: self.len, index: start+len}).into()
@@ -83,9 +97,20 @@ impl List {
         Ok(Self {
             parent: Rc::<_>::downgrade(&parent),
             start: self.start+start,
-            len
+            len,
+            readonly: self.readonly,
         })
     }
+
+    /// The same list through a reference that cannot change it;
+    /// as for PostScript arrays, other references are unaffected.
+    pub fn readonly(&self) -> Self {
+        Self {readonly: true, ..self.clone()}
+    }
+
+    pub fn writable(&self) -> bool {
+        !self.readonly
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
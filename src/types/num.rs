@@ -1,37 +1,110 @@
 use std::fmt;
+use std::cmp::Ordering;
 use std::ops::{Add, Sub, Div, Mul, Neg};
 
 use crate::error::*;
-use crate::numeric::{Number, CasterBuilder, CasterBuilderTrait, Caster, CasterTrait, CastFromFloat};
+use num_traits::cast::AsPrimitive;
+
+use crate::numeric::{Number, Scalar, Array, Value, NaN, CasterBuilder, CasterBuilderTrait, CasterTrait, CastFromFloat};
 use crate::numeric::ops::*;
 use crate::numeric::ops_defs::*;
+use crate::numeric::reduce::ReduceOp;
 use crate::numeric::primitive::NumericPrimitive;
+use crate::numeric::complex::{self, Complex};
+use crate::numeric::exact::{self, Exact, ExactValue};
+use crate::numeric::policy;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::Zero;
+
+use super::bool::{Bool, Truth};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Num {
     Int(Number<i64>),
     Float(Number<f64>),
     USize(Number<usize>),
+    Byte(Number<u8>),
+    Int32(Number<i32>),
+    Float32(Number<f32>),
+    Int128(Number<i128>),
+    Complex(Number<Complex>),
+    BigInt(Option<BigInt>),
+    Rational(Option<BigRational>),
 }
 
-impl From<Number<i64>> for Num {
-    fn from(item: Number<i64>) -> Self {Num::Int(item)}
+macro_rules! num_from {
+    ($($prim:ty => $variant:ident),+) => {
+        $(
+            impl From<Number<$prim>> for Num {
+                fn from(item: Number<$prim>) -> Self {Num::$variant(item)}
+            }
+        )+
+    };
 }
 
-impl From<Number<f64>> for Num {
-    fn from(item: Number<f64>) -> Self {Num::Float(item)}
+num_from!(
+    i64   => Int,
+    f64   => Float,
+    usize => USize,
+    u8    => Byte,
+    i32   => Int32,
+    f32   => Float32,
+    i128  => Int128,
+    Complex => Complex
+);
+
+/// Matches num against each variant, with number bound to its Number;
+/// $exact is the result for the exact numbers, which are not Numbers.
+macro_rules! each_num {
+    ($num:expr, $number:pat => $body:expr, $exact:expr) => {
+        match $num {
+            Num::Int($number)     => $body,
+            Num::Float($number)   => $body,
+            Num::USize($number)   => $body,
+            Num::Byte($number)    => $body,
+            Num::Int32($number)   => $body,
+            Num::Float32($number) => $body,
+            Num::Int128($number)  => $body,
+            Num::Complex($number) => $body,
+            Num::BigInt(_) | Num::Rational(_) => $exact,
+        }
+    };
 }
 
-impl From<Number<usize>> for Num {
-    fn from(item: Number<usize>) -> Self {Num::USize(item)}
+/// Matches a pair of nums of the same variant against lhs and rhs;
+/// $other is the result for any other pair.
+macro_rules! same_num {
+    ($pair:expr, ($lhs:pat, $rhs:pat) => $body:expr, $other:expr) => {
+        match $pair {
+            (Num::Int($lhs),     Num::Int($rhs))     => $body,
+            (Num::Float($lhs),   Num::Float($rhs))   => $body,
+            (Num::USize($lhs),   Num::USize($rhs))   => $body,
+            (Num::Byte($lhs),    Num::Byte($rhs))    => $body,
+            (Num::Int32($lhs),   Num::Int32($rhs))   => $body,
+            (Num::Float32($lhs), Num::Float32($rhs)) => $body,
+            (Num::Int128($lhs),  Num::Int128($rhs))  => $body,
+            (Num::Complex($lhs), Num::Complex($rhs)) => $body,
+            _ => $other,
+        }
+    };
 }
 
 impl Num {
+    pub fn apply_reduce<R: ReduceOp>(self, skip: bool) -> Result<Num, Error> {
+        each_num!(self, number => R::apply(number, skip), Error::OpType.into())
+    }
+
     pub fn apply_monadic<M: MonadicOp>(self) -> Result<Num, Error> {
-        match self {
-            Num::Int(i)   => Ok(M::apply(i)?.into()),
-            Num::Float(f) => Ok(M::apply(f)?.into()),
-            Num::USize(u) => Ok(M::apply(u)?.into()),
+        let ratio = matches!(self, Num::Rational(_));
+        let value = match self {
+            Num::BigInt(_) | Num::Rational(_) => self.to_exact()?,
+            num => return each_num!(num, number => Ok(M::apply(number)?.into()), unreachable!()),
+        };
+        let Some(value) = value else {return Ok(Num::exact(None, ratio))};
+        match M::exact(&value) {
+            Some(value) => Ok(Num::exact(policy::settle_exact(value)?, ratio)),
+            None => self.convert("float")?.apply_monadic::<M>(),
         }
     }
 
@@ -54,25 +127,362 @@ impl Num {
         D: DyadicOp,
         Num: From<Number<T>>,
         B: CasterBuilderTrait<T>,
-        Caster::<T, i64>: CasterTrait<T, i64>,
-        Caster::<T, f64>: CasterTrait<T, f64>,
-        Caster::<T, usize>: CasterTrait<T, usize>,
     {
         match rhs {
-            Num::Int(rhs)   => Self::apply_dyadic_strip::<D, B::Caster_i64, _, _>(lhs, rhs),
-            Num::Float(rhs) => Self::apply_dyadic_strip::<D, B::Caster_f64, _, _>(lhs, rhs),
-            Num::USize(rhs) => Self::apply_dyadic_strip::<D, B::Caster_usize, _, _>(lhs, rhs),
+            Num::Int(rhs)     => Self::apply_dyadic_strip::<D, B::Caster_i64, _, _>(lhs, rhs),
+            Num::Float(rhs)   => Self::apply_dyadic_strip::<D, B::Caster_f64, _, _>(lhs, rhs),
+            Num::USize(rhs)   => Self::apply_dyadic_strip::<D, B::Caster_usize, _, _>(lhs, rhs),
+            Num::Byte(rhs)    => Self::apply_dyadic_strip::<D, B::Caster_u8, _, _>(lhs, rhs),
+            Num::Int32(rhs)   => Self::apply_dyadic_strip::<D, B::Caster_i32, _, _>(lhs, rhs),
+            Num::Float32(rhs) => Self::apply_dyadic_strip::<D, B::Caster_f32, _, _>(lhs, rhs),
+            Num::Int128(rhs)  => Self::apply_dyadic_strip::<D, B::Caster_i128, _, _>(lhs, rhs),
+            Num::Complex(rhs) => Self::apply_dyadic_strip::<D, B::Caster_Complex, _, _>(lhs, rhs),
+            Num::BigInt(_) | Num::Rational(_) => unreachable!("exact numbers are worked on apart"),
         }
     }
 
     pub fn apply_dyadic<D: DyadicOp>(self, rhs: Num) -> Result<Num, Error> {
+        if let Some(elem) = Self::exact_pair(&self, &rhs) {
+            if elem != "exact" {
+                return self.convert(elem)?.apply_dyadic::<D>(rhs.convert(elem)?)
+            };
+            let ratio = matches!(self, Num::Rational(_)) || matches!(rhs, Num::Rational(_));
+            let (Some(lhs), Some(rhs)) = (self.to_exact()?, rhs.to_exact()?) else {
+                return Ok(Num::exact(None, ratio))
+            };
+            return match D::exact(&lhs, &rhs) {
+                Some(value) => Ok(Num::exact(policy::settle_exact(value)?, ratio)),
+                None => Num::from(lhs).convert("float")?.apply_dyadic::<D>(Num::from(rhs)),
+            }
+        };
+        each_num!(self,
+                  lhs => Self::apply_dyadic_target::<D, CasterBuilder::<_>, _>(lhs, rhs),
+                  unreachable!())
+    }
+
+    fn apply_compare_target<D, B, T>(lhs: Number<T>, rhs: Num)
+        -> Result<Bool, Error>
+    where
+        T: NumericPrimitive + CastFromFloat,
+        D: CompareOp,
+        B: CasterBuilderTrait<T>,
+    {
+        match rhs {
+            Num::Int(rhs)     => D::apply::<B::Caster_i64, _, _>(lhs, rhs),
+            Num::Float(rhs)   => D::apply::<B::Caster_f64, _, _>(lhs, rhs),
+            Num::USize(rhs)   => D::apply::<B::Caster_usize, _, _>(lhs, rhs),
+            Num::Byte(rhs)    => D::apply::<B::Caster_u8, _, _>(lhs, rhs),
+            Num::Int32(rhs)   => D::apply::<B::Caster_i32, _, _>(lhs, rhs),
+            Num::Float32(rhs) => D::apply::<B::Caster_f32, _, _>(lhs, rhs),
+            Num::Int128(rhs)  => D::apply::<B::Caster_i128, _, _>(lhs, rhs),
+            Num::Complex(rhs) => D::apply::<B::Caster_Complex, _, _>(lhs, rhs),
+            Num::BigInt(_) | Num::Rational(_) => unreachable!("exact numbers are compared apart"),
+        }
+    }
+
+    pub fn apply_compare<D: CompareOp>(self, rhs: Num) -> Result<Bool, Error> {
+        if let Some(elem) = Self::exact_pair(&self, &rhs) {
+            if elem != "exact" {
+                return self.convert(elem)?.apply_compare::<D>(rhs.convert(elem)?)
+            };
+            let (Some(lhs), Some(rhs)) = (self.to_exact()?, rhs.to_exact()?) else {
+                return Ok(Bool::Scalar(None))
+            };
+            return Ok(lhs.partial_cmp(&rhs).map(D::test).into())
+        };
+        each_num!(self,
+                  lhs => Self::apply_compare_target::<D, CasterBuilder::<_>, _>(lhs, rhs),
+                  unreachable!())
+    }
+
+    /// With an exact number on either side, "complex" or "float" if the
+    /// other is one of those, for both to be worked on as such, and
+    /// otherwise "exact"; None without an exact number.
+    fn exact_pair(lhs: &Num, rhs: &Num) -> Option<&'static str> {
+        if !lhs.is_exact() && !rhs.is_exact() {return None};
+        let complex = |num: &Num| matches!(num, Num::Complex(_));
+        if complex(lhs) || complex(rhs) {
+            Some("complex")
+        } else if lhs.is_float() || rhs.is_float() {
+            Some("float")
+        } else {
+            Some("exact")
+        }
+    }
+
+    /// An exact result, an integer or ratio as worked on; NaN is a ratio
+    /// if ratio.
+    fn exact(value: ExactValue, ratio: bool) -> Num {
+        match value {
+            Some(value) => value.into(),
+            None if ratio => Num::Rational(None),
+            None => Num::BigInt(None),
+        }
+    }
+
+    /// The value of a scalar as an exact number, NaN being None.
+    fn to_exact(&self) -> Result<ExactValue, Error> {
         match self {
-            Num::Int(lhs)   =>
-                Self::apply_dyadic_target::<D, CasterBuilder::<i64>, _>(lhs, rhs),
-            Num::Float(lhs) =>
-                Self::apply_dyadic_target::<D, CasterBuilder::<f64>, _>(lhs, rhs),
-            Num::USize(lhs) =>
-                Self::apply_dyadic_target::<D, CasterBuilder::<usize>, _>(lhs, rhs),
+            Num::BigInt(value)   => Ok(value.clone().map(Exact::Int)),
+            Num::Rational(value) => Ok(value.clone().map(Exact::Ratio)),
+            num => each_num!(num, number => match number {
+                Scalar(Value(value)) => Ok(exact::from_primitive(*value)),
+                Scalar(NaN) => Ok(None),
+                Array(_) => Error::OpType.into(),
+            }, unreachable!()),
+        }
+    }
+
+    pub fn is_exact(&self) -> bool {
+        matches!(self, Num::BigInt(_) | Num::Rational(_))
+    }
+}
+
+impl From<Exact> for Num {
+    fn from(item: Exact) -> Self {
+        match item {
+            Exact::Int(int)     => Num::BigInt(Some(int)),
+            Exact::Ratio(ratio) => Num::Rational(Some(ratio)),
+        }
+    }
+}
+
+impl Num {
+    pub fn to<U>(self) -> Result<Num, Error> where
+        U: NumericPrimitive,
+        Num: From<Number<U>>
+    {
+        each_num!(self, number => Ok(number.to_number::<U>()?.into()), match self {
+            Num::BigInt(value)   => Ok(Scalar(exact::to_scalar(&value)?).into()),
+            Num::Rational(value) => Ok(Scalar(exact::to_scalar(&value)?).into()),
+            _ => unreachable!(),
+        })
+    }
+
+    /// Converts to the element type named by elem, as reported by elem_type.
+    pub fn convert(self, elem: &str) -> Result<Num, Error> {
+        // as casts go through reals, they would lose imaginary parts
+        if elem == self.elem_type() {return Ok(self)};
+        match elem {
+            "int"     => self.to::<i64>(),
+            "float"   => self.to::<f64>(),
+            "usize"   => self.to::<usize>(),
+            "byte"    => self.to::<u8>(),
+            "int32"   => self.to::<i32>(),
+            "float32" => self.to::<f32>(),
+            "int128"  => self.to::<i128>(),
+            "complex" => self.to::<Complex>(),
+            "bigint"  => Ok(Num::BigInt(self.to_exact()?.map(|value| value.round().int()))),
+            "rational" => Ok(Num::Rational(self.to_exact()?.map(|value| value.ratio()))),
+            _ => Err(Error::Unknown(elem.into())),
+        }
+    }
+
+    /// Converts booleans to ones and zeros of the element type named by elem.
+    pub fn from_truth(truth: &Bool, elem: &str) -> Result<Num, Error> {
+        let value = |truth: &Truth| match truth {
+            Some(truth) => Value(i64::from(*truth)),
+            None => NaN,
+        };
+        let num = match truth {
+            Bool::Scalar(truth) => Num::Int(Scalar(value(truth))),
+            Bool::Array(array)  => Num::Int(array.iter().map(value).collect::<Vec<_>>().into()),
+        };
+        num.convert(elem)
+    }
+
+    pub fn truth(&self) -> Bool {
+        each_num!(self, number => number.truth(), match self {
+            Num::BigInt(value)   => value.as_ref().map(|value| !value.is_zero()).into(),
+            Num::Rational(value) => value.as_ref().map(|value| !value.is_zero()).into(),
+            _ => unreachable!(),
+        })
+    }
+
+    pub fn elem_type(&self) -> &'static str {
+        match self {
+            Num::Int(_)     => "int",
+            Num::Float(_)   => "float",
+            Num::USize(_)   => "usize",
+            Num::Byte(_)    => "byte",
+            Num::Int32(_)   => "int32",
+            Num::Float32(_) => "float32",
+            Num::Int128(_)  => "int128",
+            Num::Complex(_) => "complex",
+            Num::BigInt(_)  => "bigint",
+            Num::Rational(_) => "rational",
+        }
+    }
+
+    pub fn is_float(&self) -> bool {
+        matches!(self, Num::Float(_) | Num::Float32(_))
+    }
+
+    pub fn lend(&mut self, lent: bool) {
+        each_num!(self, number => number.lend(lent), ())
+    }
+
+    pub fn cardinality(&self) -> &'static str {
+        each_num!(self, number => number.cardinality(), "scalar")
+    }
+
+    /// The number of elements of an array.
+    pub fn length(&self) -> Result<usize, Error> {
+        each_num!(self, number => match number {
+            Array(array) => Ok(array.len()),
+            Scalar(_) => Error::OpType.into(),
+        }, Error::OpType.into())
+    }
+
+    pub fn get(&self, index: usize) -> Result<Num, Error> {
+        each_num!(self, number => match number {
+            Array(array) => Ok(Scalar(array.get(index)?).into()),
+            Scalar(_) => Error::OpType.into(),
+        }, Error::OpType.into())
+    }
+
+    /// Stores a scalar, converted to the element type, in the array
+    /// and every view sharing its elements.
+    pub fn put(&mut self, index: usize, value: Num) -> Option<Error> {
+        let value = match value.convert(self.elem_type()) {
+            Ok(value) => value,
+            Err(err) => return Some(err),
+        };
+        same_num!((self, value), (Array(array), Scalar(value)) => array.put(index, value),
+                  Some(Error::OpType))
+    }
+
+    /// Copies the elements of an array, converted to the element type,
+    /// over those of this one from start on.
+    pub fn put_interval(&mut self, start: usize, values: Num) -> Option<Error> {
+        let values = match values.convert(self.elem_type()) {
+            Ok(values) => values,
+            Err(err) => return Some(err),
+        };
+        same_num!((self, values), (Array(array), Array(values)) =>
+                      array.put_interval(start, &values.to_vec()),
+                  Some(Error::OpType))
+    }
+
+    /// A view of len elements, stride apart from start on,
+    /// sharing the elements of the array.
+    pub fn range(&self, start: usize, len: usize, stride: usize) -> Result<Num, Error> {
+        each_num!(self, number => match number {
+            Array(array) => Ok(Array(array.range(start, len, stride)?).into()),
+            Scalar(_) => Error::OpType.into(),
+        }, Error::OpType.into())
+    }
+
+    /// The same array through a reference that cannot change it.
+    pub fn readonly(&self) -> Result<Num, Error> {
+        each_num!(self, number => match number {
+            Array(array) => Ok(Array(array.readonly()).into()),
+            Scalar(_) => Error::OpType.into(),
+        }, Error::OpType.into())
+    }
+
+    pub fn writable(&self) -> Result<bool, Error> {
+        each_num!(self, number => match number {
+            Array(array) => Ok(array.writable()),
+            Scalar(_) => Error::OpType.into(),
+        }, Error::OpType.into())
+    }
+
+    /// An array of count copies of a scalar.
+    pub fn fill(&self, count: usize) -> Result<Num, Error> {
+        each_num!(self, number => match number {
+            Scalar(value) => Ok(Number::from(vec![*value; count]).into()),
+            Array(_) => Error::OpType.into(),
+        }, Error::OpType.into())
+    }
+
+    /// The elements of both, those of other converted to the element type.
+    pub fn concat(&self, other: Num) -> Result<Num, Error> {
+        same_num!((self, other.convert(self.elem_type())?), (lhs, rhs) =>
+                      Ok(lhs.concat(&rhs).into()),
+                  Error::OpType.into())
+    }
+
+    pub fn reverse(&self) -> Result<Num, Error> {
+        each_num!(self, number => Ok(number.reverse().into()), Error::OpType.into())
+    }
+
+    pub fn repeat(&self, count: usize) -> Result<Num, Error> {
+        each_num!(self, number => Ok(number.repeat(count).into()), Error::OpType.into())
+    }
+
+    pub fn tile(&self, count: usize) -> Result<Num, Error> {
+        each_num!(self, number => Ok(number.tile(count).into()), Error::OpType.into())
+    }
+
+    /// A part of each element as a float, reals being complex numbers
+    /// without an imaginary part.
+    pub fn complex_part(self, part: fn(Complex) -> f64) -> Result<Num, Error> {
+        let Num::Complex(number) = self.convert("complex")? else {unreachable!()};
+        Ok(Num::Float(complex::part(&number, part)))
+    }
+
+    pub fn conj(self) -> Num {
+        match self {
+            Num::Complex(number) => Num::Complex(complex::map(&number, Complex::conj)),
+            num => num,
+        }
+    }
+
+    /// The magnitude, as a float for complex numbers.
+    pub fn abs(self) -> Result<Num, Error> {
+        match self {
+            Num::Complex(number) => Ok(Num::Float(complex::part(&number, Complex::norm))),
+            num => num.apply_monadic::<AbsOp>(),
+        }
+    }
+
+    /// Complex numbers from magnitudes r and angles theta.
+    pub fn polar(r: Num, theta: Num) -> Result<Num, Error> {
+        let (Num::Float(r), Num::Float(theta)) = (r.convert("float")?, theta.convert("float")?)
+        else {unreachable!()};
+        Ok(Num::Complex(complex::polar(r, theta)?))
+    }
+
+    fn scalar_as<P>(&self) -> Result<Option<P>, Error> where
+        P: Copy + 'static,
+        i64: AsPrimitive<P>,
+        f64: AsPrimitive<P>,
+        usize: AsPrimitive<P>,
+        u8: AsPrimitive<P>,
+        i32: AsPrimitive<P>,
+        f32: AsPrimitive<P>,
+        i128: AsPrimitive<P>,
+        Complex: AsPrimitive<P>,
+    {
+        each_num!(self, number => match number {
+            Scalar(Value(v)) => Ok(Some(v.as_())),
+            Scalar(_) => Ok(None),
+            Array(_) => Error::OpType.into(),
+        }, Error::OpType.into())
+    }
+
+    /// Orders two scalars, promoting to float if either side is a float
+    /// or complex, which compare by their real value, and otherwise to an
+    /// exact number if either side is one. NaN compares as None,
+    /// arrays are an OpType error.
+    pub fn compare(&self, other: &Num) -> Result<Option<Ordering>, Error> {
+        let complex = |num: &Num| matches!(num, Num::Complex(_));
+        if self.is_float() || other.is_float() || complex(self) || complex(other) {
+            if self.is_exact() || other.is_exact() {
+                return self.clone().convert("float")?.compare(&other.clone().convert("float")?)
+            };
+            let (Some(lhs), Some(rhs)) = (self.scalar_as::<f64>()?, other.scalar_as::<f64>()?)
+            else {return Ok(None)};
+            Ok(lhs.partial_cmp(&rhs))
+        } else if self.is_exact() || other.is_exact() {
+            let (Some(lhs), Some(rhs)) = (self.to_exact()?, other.to_exact()?)
+            else {return Ok(None)};
+            Ok(lhs.partial_cmp(&rhs))
+        } else {
+            let (Some(lhs), Some(rhs)) = (self.scalar_as::<i128>()?, other.scalar_as::<i128>()?)
+            else {return Ok(None)};
+            Ok(Some(lhs.cmp(&rhs)))
         }
     }
 }
@@ -108,10 +518,10 @@ impl Div for Num {
 
 impl fmt::Display for Num {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Num::Int(i)   => write!(f, "{i}"),
-            Num::Float(x) => write!(f, "{x}"),
-            Num::USize(i) => write!(f, "{i}"),
-        }
+        each_num!(self, number => write!(f, "{number}"), match self {
+            Num::BigInt(Some(int))     => write!(f, "{int}n"),
+            Num::Rational(Some(ratio)) => write!(f, "{}:{}", ratio.numer(), ratio.denom()),
+            _ => write!(f, "*"),
+        })
     }
 }
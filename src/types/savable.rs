@@ -70,6 +70,10 @@ pub trait HasNew {
     }
 
     fn weak_parent(&self) -> Weak<RefCell<Saved>>;
+
+    fn addr(&self) -> usize {
+        self.weak_parent().as_ptr() as usize
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -79,6 +83,10 @@ impl RcSaved {
     fn new(saved: Saved) -> Self {
         RcSaved(Rc::new(RefCell::new(saved)))
     }
+
+    pub fn addr(&self) -> usize {
+        Rc::as_ptr(&self.0) as usize
+    }
 }
 
 impl PartialEq for RcSaved {
@@ -1,6 +1,7 @@
 use std::rc::{Rc, Weak};
 use std::cell::RefCell;
 use std::fmt;
+use std::collections::{HashSet, HashMap};
 
 use crate::error::Error;
 
@@ -9,16 +10,25 @@ use super::savable::{Saved, Unwrap, HasNew, Intern, RcSaved};
 #[derive(Clone, Debug)]
 pub struct Save {
     savebox: Vec<RcSaved>,
+    allocated: HashSet<usize>,
+    /// The contents of objects allocated at older levels, as they were
+    /// before they were first changed at this one.
+    kept: HashMap<usize, (Weak<RefCell<Saved>>, Saved)>,
 }
 
 impl Save {
     pub fn new() -> Self {
-        Save {savebox: Vec::<_>::new()}
+        Save {savebox: Vec::<_>::new(), allocated: HashSet::new(), kept: HashMap::new()}
     }
 
     pub fn insert(&mut self, saved: &RcSaved) {
+        self.allocated.insert(saved.addr());
         self.savebox.push(saved.clone());
     }
+
+    pub fn addrs(&self) -> impl Iterator<Item=usize> + '_ {
+        self.savebox.iter().map(RcSaved::addr)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -53,6 +63,12 @@ impl SaveBox {
         Self {_pinned: Some(parent.clone()), ..Self::new(&parent)}
     }
 
+    /// An unpinned reference to this save level, which does not keep
+    /// the level alive once it has been popped from the save stack.
+    pub fn handle(&self) -> Self {
+        Self {parent: self.parent.clone(), _pinned: None}
+    }
+
     pub fn len(&self) -> Result<usize, Error> {
         let parent = self.get_parent()?;
         let saved = &*parent.borrow();
@@ -60,6 +76,47 @@ impl SaveBox {
         Ok(save.savebox.len())
     }
 
+    /// Adds the addresses of every object allocated at this level to addrs.
+    pub fn collect(&self, addrs: &mut HashSet<usize>) -> Option<Error> {
+        let parent = match self.get_parent() {
+            Err(err) => return Some(err),
+            Ok(parent) => parent,
+        };
+        let saved = &*parent.borrow();
+        let save: &Save = saved.unwrap();
+        addrs.extend(save.addrs());
+        None
+    }
+
+    /// Keeps the contents of obj, unless it was allocated at this level
+    /// or they are kept already, for undo to put back.
+    pub fn keep<T: HasNew>(&mut self, obj: &T) -> Result<(), Error> {
+        let contents = obj.get_parent()?;
+        let parent = self.get_parent()?;
+        let saved = &mut *parent.borrow_mut();
+        let save: &mut Save = saved.unwrap_mut();
+        let addr = obj.addr();
+        if !save.allocated.contains(&addr) && !save.kept.contains_key(&addr) {
+            let kept = contents.borrow().clone();
+            save.kept.insert(addr, (Rc::downgrade(&contents), kept));
+        };
+        Ok(())
+    }
+
+    /// Puts back the contents of every object changed at this level as
+    /// they were before the change.
+    pub fn undo(&self) -> Result<(), Error> {
+        let parent = self.get_parent()?;
+        let saved = &*parent.borrow();
+        let save: &Save = saved.unwrap();
+        for (contents, kept) in save.kept.values() {
+            if let Some(contents) = contents.upgrade() {
+                *contents.borrow_mut() = kept.clone();
+            }
+        };
+        Ok(())
+    }
+
     pub fn put<T: Intern>(&mut self, obj: T) -> Result<T::Interned, Error> {
         let parent = self.get_parent()?;
         let parent = &mut *parent.borrow_mut();
@@ -2,36 +2,70 @@ use std::fmt;
 use std::convert::From;
 use std::collections::{HashMap, VecDeque};
 use std::borrow::Borrow;
+use std::rc::Rc;
 
 use crate::ext::term;
 use crate::error::*;
 use crate::reader::Reader;
+use crate::lexer::{Source, escape};
 use crate::types::save::SaveBox;
+use crate::types::savable::HasNew;
 use crate::types::list::List;
 use crate::types::dict::Dict;
 
 pub use crate::types::name::{Name, InternTable};
 pub use crate::types::num::Num;
+pub use crate::types::bool::Bool;
 use crate::numeric::{Scalar, NaN};
+use crate::numeric::policy::{self, Policy};
 
 pub(crate) mod optypes;
 pub(crate) mod unaryops;
 pub(crate) mod binaryops;
+pub(crate) mod complexops;
 pub(crate) mod naryops;
+pub(crate) mod reduceops;
+pub(crate) mod convops;
+pub(crate) mod controlops;
+pub(crate) mod code;
+pub(crate) mod bindops;
+pub(crate) mod arrayops;
+pub(crate) mod linalgops;
+pub(crate) mod boolops;
+pub(crate) mod dictops;
+pub(crate) mod saveops;
+pub(crate) mod policyops;
 mod stackops;
 pub mod ops;
 mod vminfo;
 
 use optypes::*;
+use controlops::Control;
+use code::Code;
 use vminfo::Vminfo;
 
-#[derive(Debug, Clone, PartialEq)]
+/// An executable frame; names remember where they were read, if they were.
+#[derive(Debug, Clone)]
 pub enum Active {
     String(String),
-    Name(Name),
+    Name(Name, Option<Rc<Source>>),
     Mark,
     EndMark,
     List(List),
+    Code(Code),
+}
+
+impl PartialEq for Active {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Active::String(lhs), Active::String(rhs)) => lhs == rhs,
+            (Active::Name(lhs, _), Active::Name(rhs, _)) => lhs == rhs,
+            (Active::Mark, Active::Mark) | (Active::EndMark, Active::EndMark) => true,
+            (Active::List(lhs), Active::List(rhs)) => lhs == rhs,
+            (Active::Code(lhs), Active::Code(rhs)) => lhs == rhs,
+            _ => false,
+        }
+    }
 }
 
 impl From<String> for Active {
@@ -42,7 +76,7 @@ impl From<String> for Active {
 
 impl From<Name> for Active {
     fn from(item: Name) -> Self {
-        Active::Name(item)
+        Active::Name(item, None)
     }
 }
 
@@ -52,14 +86,27 @@ impl From<List> for Active {
     }
 }
 
+impl From<Passive> for Active {
+    fn from(item: Passive) -> Self {
+        match item {
+            Passive::Name(name)     => Active::Name(name, None),
+            Passive::String(string) => Active::String(string),
+            Passive::Mark           => Active::Mark,
+            Passive::EndMark        => Active::EndMark,
+            Passive::List(list)     => Active::List(list),
+        }
+    }
+}
+
 impl fmt::Display for Active {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match &self {
-            Active::String(string) => write!(f, "~({string})"),
-            Active::Name(name) => write!(f, "~/({name})"),
+            Active::String(string) => write!(f, "~({})", escape(string)),
+            Active::Name(name, _) => write!(f, "~/({name})"),
             Active::Mark => write!(f, "{{"),
             Active::EndMark => write!(f, "}}"),
             Active::List(list) => write!(f, "{{ {list} }}"),
+            Active::Code(code) => write!(f, "{{ {code} }}"),
         }
     }
 }
@@ -91,10 +138,23 @@ impl From<List> for Passive {
     }
 }
 
+impl From<Active> for Passive {
+    fn from(item: Active) -> Self {
+        match item {
+            Active::Name(name, _)  => Passive::Name(name),
+            Active::String(string) => Passive::String(string),
+            Active::Mark           => Passive::Mark,
+            Active::EndMark        => Passive::EndMark,
+            Active::List(list)     => Passive::List(list),
+            Active::Code(code)     => Passive::List(code.list().clone()),
+        }
+    }
+}
+
 impl fmt::Display for Passive {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match &self {
-            Passive::String(string) => write!(f, "({string})"),
+            Passive::String(string) => write!(f, "({})", escape(string)),
             Passive::Name(name) => write!(f, "/({name})"),
             Passive::Mark => write!(f, "["),
             Passive::EndMark => write!(f, "]"),
@@ -103,15 +163,34 @@ impl fmt::Display for Passive {
     }
 }
 
+/// An operator bound into a procedure in place of a name, remembering
+/// where the name was read.
+#[derive(Debug, Clone)]
+pub struct Bound {
+    pub op: Box<Frame>,
+    pub source: Rc<Source>,
+}
+
+impl PartialEq for Bound {
+    fn eq(&self, other: &Self) -> bool {
+        self.op == other.op
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Frame {
     Num(Num),
+    Bool(Bool),
     Null,
     UnaryOp(UnaryOp),
     BinaryOp(BinaryOp),
     StackOp(StackOp),
     NaryOp(NaryOp),
     VmOp(VmOp),
+    Bound(Bound),
+    Control(Control),
+    Dict(Dict),
+    Save(SaveBox),
     Active(Active),
     Passive(Passive),
 }
@@ -122,6 +201,12 @@ impl From<Num> for Frame {
     }
 }
 
+impl From<Bool> for Frame {
+    fn from(item: Bool) -> Self {
+        Frame::Bool(item)
+    }
+}
+
 impl From<UnaryOp> for Frame {
     fn from(item: UnaryOp) -> Self {
         Frame::UnaryOp(item)
@@ -152,6 +237,24 @@ impl From<VmOp> for Frame {
     }
 }
 
+impl From<Bound> for Frame {
+    fn from(item: Bound) -> Self {
+        Frame::Bound(item)
+    }
+}
+
+impl From<Dict> for Frame {
+    fn from(item: Dict) -> Self {
+        Frame::Dict(item)
+    }
+}
+
+impl From<SaveBox> for Frame {
+    fn from(item: SaveBox) -> Self {
+        Frame::Save(item)
+    }
+}
+
 impl From<Passive> for Frame {
     fn from(item: Passive) -> Self {
         Frame::Passive(item)
@@ -169,25 +272,117 @@ impl fmt::Display for Frame {
         match &self {
             Frame::Null           => write!(f, "null"),
             Frame::Num(v)         => write!(f, "{v}"),
+            Frame::Bool(b)        => write!(f, "{b}"),
             Frame::StackOp(op)    => write!(f, "{op}"),
             Frame::VmOp(op)       => write!(f, "{op}"),
             Frame::UnaryOp(op)    => write!(f, "{op}"),
             Frame::BinaryOp(op)   => write!(f, "{op}"),
             Frame::NaryOp(op)     => write!(f, "{op}"),
+            Frame::Bound(bound)   => write!(f, "{}", bound.op),
+            Frame::Control(ctrl)  => write!(f, "{ctrl}"),
+            Frame::Dict(dict)     => write!(f, "<< {dict} >>"),
+            Frame::Save(save)     => write!(f, "--save {save}--"),
             Frame::Active(frame)  => write!(f, "{frame}"),
             Frame::Passive(frame) => write!(f, "{frame}"),
         }
     }
 }
 
+impl Frame {
+    pub fn is_op(&self) -> bool {
+        matches!(self,
+            Frame::UnaryOp(_) | Frame::BinaryOp(_) | Frame::StackOp(_)
+            | Frame::NaryOp(_) | Frame::VmOp(_) | Frame::Bound(_))
+    }
+
+    /// The name returned by the type operator.
+    pub fn type_name(&self) -> String {
+        let name = match self {
+            Frame::Num(num) => return format!("{}{}", num.elem_type(), num.cardinality()),
+            Frame::Bool(Bool::Scalar(_)) => "boolscalar",
+            Frame::Bool(Bool::Array(_))  => "boolarray",
+            Frame::Null => "null",
+            Frame::UnaryOp(_) | Frame::BinaryOp(_) | Frame::StackOp(_)
+                | Frame::NaryOp(_) | Frame::VmOp(_) | Frame::Bound(_) => "operator",
+            Frame::Control(_) => "control",
+            Frame::Dict(_) => "dict",
+            Frame::Save(_) => "save",
+            Frame::Active(Active::String(_)) | Frame::Passive(Passive::String(_)) => "string",
+            Frame::Active(Active::Name(..))  | Frame::Passive(Passive::Name(_))   => "name",
+            Frame::Active(Active::List(_) | Active::Code(_))
+                | Frame::Passive(Passive::List(_)) => "list",
+            Frame::Active(Active::Mark | Active::EndMark)
+                | Frame::Passive(Passive::Mark | Passive::EndMark) => "mark",
+        };
+        name.into()
+    }
+}
+
 fn base_map(t: &mut InternTable) -> HashMap<Name, Frame> {
     let mut dict = HashMap::from_iter([
         &unaryops::NEG as &dyn optypes::Op,
         &unaryops::COS as &dyn optypes::Op,
+        &unaryops::SIN,
+        &unaryops::TAN,
+        &unaryops::ASIN,
+        &unaryops::ACOS,
+        &unaryops::ATAN,
+        &unaryops::SINH,
+        &unaryops::COSH,
+        &unaryops::TANH,
+        &unaryops::EXP,
+        &unaryops::LN,
+        &unaryops::LOG10,
+        &unaryops::LOG2,
+        &unaryops::SQRT,
+        &unaryops::ABS,
+        &unaryops::FLOOR,
+        &unaryops::CEIL,
+        &unaryops::ROUND,
+        &unaryops::TRUNC,
         &binaryops::ADD,
         &binaryops::SUB,
         &binaryops::MUL,
         &binaryops::DIV,
+        &binaryops::ATAN2,
+        &binaryops::POW,
+        &binaryops::MOD,
+        &binaryops::REM,
+        &binaryops::MIN,
+        &binaryops::MAX,
+        &complexops::RE,
+        &complexops::IM,
+        &complexops::ARG,
+        &complexops::CONJ,
+        &complexops::POLAR,
+        &reduceops::SUM,
+        &reduceops::PROD,
+        &reduceops::AMIN,
+        &reduceops::AMAX,
+        &reduceops::MEAN,
+        &reduceops::VAR,
+        &reduceops::STD,
+        &reduceops::ARGMIN,
+        &reduceops::ARGMAX,
+        &reduceops::COUNT,
+        &reduceops::NANSUM,
+        &reduceops::NANPROD,
+        &reduceops::NANAMIN,
+        &reduceops::NANAMAX,
+        &reduceops::NANMEAN,
+        &reduceops::NANVAR,
+        &reduceops::NANSTD,
+        &reduceops::NANARGMIN,
+        &reduceops::NANARGMAX,
+        &convops::CVI,
+        &convops::CVD,
+        &convops::CVU,
+        &convops::CVB,
+        &convops::CVS,
+        &convops::CVF,
+        &convops::CVQ,
+        &convops::CVT,
+        &convops::TYPE,
         &stackops::CLEAR,
         &stackops::SHOW,
         &stackops::PEEK,
@@ -203,10 +398,71 @@ fn base_map(t: &mut InternTable) -> HashMap<Name, Frame> {
         &naryops::PUT,
         &naryops::LENGTH,
         &naryops::GETINTERVAL,
+        &naryops::SLICE,
+        &naryops::PUTINTERVAL,
+        &arrayops::RAMP,
+        &arrayops::LINSPACE,
+        &arrayops::FILL,
+        &arrayops::ZEROS,
+        &arrayops::ONES,
+        &arrayops::NANS,
+        &arrayops::CONCAT,
+        &arrayops::CAT,
+        &arrayops::REVERSE,
+        &arrayops::REPLICATE,
+        &arrayops::TILE,
+        &linalgops::DOT,
+        &linalgops::MATMUL,
+        &linalgops::OUTER,
+        &linalgops::TRANSPOSE,
+        &linalgops::INV,
+        &linalgops::DET,
+        &linalgops::SOLVE,
+        &linalgops::QR,
+        &linalgops::LSTSQ,
         &naryops::QUIT,
         &naryops::MKSTR,
         &naryops::MKPASS,
         &naryops::MKACT,
+        &naryops::XCHECK,
+        &naryops::CVX,
+        &naryops::CVLIT,
+        &controlops::IF,
+        &controlops::IFELSE,
+        &controlops::REPEAT,
+        &controlops::FOR,
+        &controlops::LOOP,
+        &controlops::EXIT,
+        &controlops::STOP,
+        &controlops::STOPPED,
+        &code::COMPILE,
+        &bindops::BIND,
+        &bindops::READONLY,
+        &bindops::WCHECK,
+        &boolops::EQ,
+        &boolops::NE,
+        &boolops::LT,
+        &boolops::LE,
+        &boolops::GT,
+        &boolops::GE,
+        &boolops::AND,
+        &boolops::OR,
+        &boolops::XOR,
+        &boolops::NOT,
+        &dictops::DICT,
+        &dictops::BEGIN,
+        &dictops::END,
+        &dictops::DEF,
+        &dictops::LOAD,
+        &dictops::KNOWN,
+        &dictops::WHERE,
+        &dictops::UNDEF,
+        &dictops::CURRENTDICT,
+        &dictops::COUNTDICTSTACK,
+        &saveops::SAVE,
+        &saveops::RESTORE,
+        &policyops::SETNUMERICPOLICY,
+        &policyops::NUMERICPOLICY,
         &vminfo::VMSTATUS,
     ].into_iter()
      .map(|op| op.mkpair(t))
@@ -223,6 +479,8 @@ fn base_map(t: &mut InternTable) -> HashMap<Name, Frame> {
         ("=",    stackops::PEEK.into()),
         ("mark", Passive::Mark.into()),
         ("null", Frame::Null),
+        ("true", Bool::from(true).into()),
+        ("false", Bool::from(false).into()),
     ].into_iter()
      .map(|(s, f)| (t.intern(s.into()), f))
     );
@@ -233,28 +491,43 @@ fn base_map(t: &mut InternTable) -> HashMap<Name, Frame> {
 pub struct Vm {
     pub(crate) op_stack: Vec<Frame>,
     pub(crate) exec_stack: Vec<Frame>,
+    pub(crate) exec_base: usize,
     pub(crate) save_stack: Vec<SaveBox>,
+    /// The numeric policy at each save level, restored along with it.
+    pub(crate) policy_stack: Vec<Policy>,
+    /// NumericPolicy, the name of the policy's entry in a dict.
+    policy_key: Name,
     pub(crate) dict_stack: VecDeque<Dict>,
+    pub(crate) errordict: Dict,
     pub(crate) vminfo: Vminfo,
     pub(crate) intern_table: InternTable,
     pub(crate) proc_depth: usize,
+    pub(crate) location: Option<Rc<Source>>,
 }
 
 impl Vm {
     pub fn new() -> Self {
         let mut intern_table = InternTable::new();
-        let map = base_map(&mut intern_table);
+        let mut map = base_map(&mut intern_table);
         let mut save = SaveBox::base();
+        let errordict = save.put(HashMap::new()).unwrap();
+        map.insert(intern_table.intern("errordict".into()), errordict.clone().into());
         let dict = save.put(map).unwrap();
+        let policy_key = intern_table.intern("NumericPolicy".into());
 
         Vm {
             op_stack: Vec::new(),
             exec_stack: Vec::new(),
+            exec_base: 0,
             save_stack: vec![save],
+            policy_stack: vec![Policy::default()],
+            policy_key,
             dict_stack: vec![dict].into(),
+            errordict,
             vminfo: Vminfo::new(),
             intern_table,
             proc_depth: 0,
+            location: None,
         }
     }
 
@@ -262,9 +535,43 @@ impl Vm {
         self.intern_table.intern(string)
     }
 
+    /// The numeric policy named by a NumericPolicy entry in the dict stack,
+    /// if there is one, or else the one set for the save level.
+    pub(crate) fn policy(&mut self) -> Result<Policy, Error> {
+        match self.find(self.policy_key.clone()) {
+            Ok(Frame::Passive(Passive::Name(name))) => {
+                let name: &String = name.borrow();
+                name.parse::<Policy>()
+            },
+            Ok(_) => Error::OpType.into(),
+            Err(Error::MissingKey(_)) => Ok(self.save_policy()),
+            Err(error) => Err(error),
+        }
+    }
+
+    pub(crate) fn save_policy(&self) -> Policy {
+        self.policy_stack.last().copied().unwrap_or_default()
+    }
+
+    /// Keeps obj as it is, for restoring the current save level to put
+    /// back; nothing restores the base level.
+    pub(crate) fn keep<T: HasNew>(&mut self, obj: &T) -> Result<(), Error> {
+        match &mut self.save_stack[..] {
+            [_, .., save] => save.keep(obj),
+            _ => Ok(()),
+        }
+    }
+
     fn exec_op<T: optypes::Op>(&mut self, op: T) -> Result<(), Error> {
-        match op.exec(self) {
-            Some(e) => e.into(),
+        let error = match self.policy() {
+            Ok(policy) => policy::with(policy, || op.exec(self)),
+            Err(error) => Some(error),
+        };
+        match error {
+            Some(e) => {
+                let command = self.intern(op.name().into());
+                self.signal(e, command, op.arity())
+            },
             None => Ok(()),
         }
     }
@@ -282,61 +589,181 @@ impl Vm {
     pub fn exec(&mut self, mut frames: Vec<Frame>) -> Result<Option<Frame>, Error>
     {
         frames.reverse();
+        let entry = self.exec_stack.len();
+        let depth = self.proc_depth;
+        let base = std::mem::replace(&mut self.exec_base, entry);
+        let location = self.location.take();
         self.exec_stack.append(&mut frames);
+        let result = self.run();
+        self.exec_base = base;
+        self.location = location;
+        if result.is_err() {
+            self.reset(entry, depth)
+        };
+        result
+    }
+
+    /// Drops whatever an exec that failed left on the execution stack
+    /// and any procedure it had started building on the operand stack.
+    fn reset(&mut self, entry: usize, depth: usize) {
+        self.exec_stack.truncate(entry);
+        while self.proc_depth > depth {
+            let mark: Frame = Active::Mark.into();
+            if let Some(index) = self.op_stack.iter().rposition(|frame| *frame == mark) {
+                self.op_stack.truncate(index)
+            };
+            self.proc_depth -= 1;
+        }
+        self.proc_depth = depth;
+    }
+
+    fn run(&mut self) -> Result<Option<Frame>, Error> {
         loop {
-            let Some(frame) = self.exec_stack.pop() else {     
+            if self.exec_stack.len() <= self.exec_base {
                 break Ok(self.peek());
             };
-            
-            match frame {
-                Frame::Active(Active::Mark) => {
-                    self.proc_depth += 1;
-                    self.op_stack.push(frame)
-                },
-
-                Frame::Active(Active::EndMark) => {
-                    assert!(self.proc_depth > 0);
-                    self.proc_depth -= 1;
-                    self.exec_op(ops::MKPROC)?
-                },
-
-                _ if self.proc_depth > 0 => self.op_stack.push(frame),
-
-                Frame::Active(Active::List(list)) => {
-                    let len = list.len()?;
-                    if len != 0 {
-                        if len > 1 {
-                            self.exec_stack.push(Active::List(list.range(1, len-1)?).into())
-                        };
-                        self.exec_stack.push(list.get(0)?)
-                    }
-                },
-                
-                Frame::Active(Active::Name(name)) => {
-                    let f = self.find(name)?;
-                    self.exec_stack.push(f)
-                },
-
-                Frame::Active(Active::String(string)) => {
-                    let reader = &mut Reader::new(self);
-                    match term::exec_string(reader, string) {
-                        None => return Error::Quit.into(),
-                        Some(Err(err)) => return err.into(),
-                        Some(Ok(())) => (),
-                    }
-                },
-                
-                Frame::UnaryOp(op)  => self.exec_op(op)?,
-                Frame::BinaryOp(op) => self.exec_op(op)?,
-                Frame::StackOp(op)  => self.exec_op(op)?,
-                Frame::VmOp(op)     => self.exec_op(op)?,
-                Frame::NaryOp(op)   => self.exec_op(op)?,
-
-                other => self.op_stack.push(other),
+            let Some(frame) = self.exec_stack.pop() else {
+                break Ok(self.peek());
+            };
+
+            if let Err(error) = self.step(frame) {
+                self.unwind(error)?
             }
         }
     }
 
+    fn step(&mut self, frame: Frame) -> Result<(), Error> {
+        match frame {
+            Frame::Control(control) => control.step(self)?,
+
+            Frame::Active(Active::Mark) => {
+                self.proc_depth += 1;
+                self.op_stack.push(frame)
+            },
+
+            Frame::Active(Active::EndMark) => {
+                self.proc_depth = self.proc_depth.saturating_sub(1);
+                self.exec_op(ops::MKPROC)?
+            },
+
+            _ if self.proc_depth > 0 => self.op_stack.push(frame),
+
+            Frame::Active(Active::List(list)) => {
+                let len = list.len()?;
+                if len != 0 {
+                    if len > 1 {
+                        self.exec_stack.push(Active::List(list.range(1, len-1)?).into())
+                    };
+                    // procedures nested in a procedure are data, as in PostScript
+                    match list.get(0)? {
+                        proc@Frame::Active(Active::List(_)) => self.op_stack.push(proc),
+                        frame => self.exec_stack.push(frame),
+                    }
+                }
+            },
+
+            Frame::Active(Active::Code(code)) => code.step(self)?,
+
+            Frame::Active(Active::Name(name, source)) => {
+                if source.is_some() {
+                    self.location = source
+                };
+                match self.find(name.clone()) {
+                    Ok(f) => self.exec_stack.push(f),
+                    Err(error) => self.signal(error, name, 0)?,
+                }
+            },
+
+            Frame::Active(Active::String(string)) => {
+                let reader = &mut Reader::new(self);
+                match term::exec_string(reader, string) {
+                    None => return Error::Quit.into(),
+                    Some(Err(err)) => return err.into(),
+                    Some(Ok(())) => (),
+                }
+            },
+
+            Frame::UnaryOp(op)  => self.exec_op(op)?,
+            Frame::BinaryOp(op) => self.exec_op(op)?,
+            Frame::StackOp(op)  => self.exec_op(op)?,
+            Frame::VmOp(op)     => self.exec_op(op)?,
+            Frame::NaryOp(op)   => self.exec_op(op)?,
+
+            Frame::Bound(Bound {op, source}) => {
+                self.location = Some(source);
+                self.step(*op)?
+            },
+
+            other => self.op_stack.push(other),
+        };
+        Ok(())
+    }
+
+    /// Runs the errordict handler for error, if there is one, with the
+    /// offending command's name pushed above its restored operands.
+    /// Otherwise the error is returned with the command and its operands.
+    fn signal(&mut self, error: Error, command: Name, arity: usize) -> Result<(), Error> {
+        if let Error::Quit | Error::Stop = error {
+            return error.into()
+        };
+
+        let key = self.intern(error.name().into());
+        let Some(handler) = self.errordict.find(&key)? else {
+            let len = self.op_stack.len();
+            let operands = self.op_stack[len-arity.min(len)..].to_vec();
+            let command = command.into();
+            let location = self.location.clone();
+            let backtrace = self.backtrace();
+            let failure = Failure {error, command, operands, location, backtrace};
+            return Error::Failed(Box::new(failure)).into()
+        };
+        self.op_stack.push(Passive::Name(command).into());
+        self.exec_stack.push(handler);
+        Ok(())
+    }
+
+    /// Where the procedures being executed were called from, innermost
+    /// first: the names that ran just before the rest of each procedure
+    /// left on the execution stack.
+    fn backtrace(&self) -> Vec<Rc<Source>> {
+        const DEPTH: usize = 8;
+        self.exec_stack.iter().rev()
+            .filter_map(|frame| match frame {
+                Frame::Active(Active::List(list)) => list.previous(),
+                Frame::Active(Active::Code(code)) => code.previous(),
+                _ => None,
+            })
+            .filter_map(|frame| match frame {
+                Frame::Active(Active::Name(_, source)) => source,
+                Frame::Bound(bound) => Some(bound.source),
+                _ => None,
+            })
+            .filter(|source| match &self.location {
+                Some(location) => !Rc::ptr_eq(source, location),
+                None => true,
+            })
+            .take(DEPTH)
+            .collect()
+    }
+
+    /// Unwinds the execution stack to the innermost stopped context
+    /// of this exec, pushing true; without one, the error escapes.
+    fn unwind(&mut self, error: Error) -> Result<(), Error> {
+        if let Error::Quit = error {
+            return error.into()
+        };
+
+        let base = self.exec_base;
+        let Some(index) = self.exec_stack[base..].iter()
+            .rposition(|frame| matches!(frame, Frame::Control(Control::Stopped)))
+        else {
+            return error.into()
+        };
+        self.exec_stack.truncate(base+index);
+        self.op_stack.push(Bool::from(true).into());
+        Ok(())
+    }
+
     pub fn peek(&self) -> Option<Frame> {
         let frame = self.op_stack.last()?;
         Some(frame.clone())
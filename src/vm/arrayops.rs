@@ -0,0 +1,149 @@
+use itertools::Itertools;
+
+use super::*;
+use crate::error::Error;
+use super::optypes::NaryOp;
+use super::naryops::from_num;
+use crate::numeric::{Scalar, Value, NaN};
+
+fn scalar(frame: Frame) -> Result<Num, Error> {
+    match frame {
+        Frame::Num(num) if num.cardinality() == "scalar" => Ok(num),
+        _ => Error::OpType.into(),
+    }
+}
+
+fn elem(frame: Frame) -> Result<String, Error> {
+    let Frame::Passive(Passive::Name(name)) = frame else {
+        return Error::OpType.into()
+    };
+    let elem: &String = name.borrow();
+    Ok(elem.clone())
+}
+
+/// start step count ramp: start, start+step and so on, as complex
+/// numbers or floats if either is one, the wider float if both are floats,
+/// and otherwise of the type of start. Exact numbers have no arrays, so
+/// neither start nor step may be one.
+fn framp(stack: Vec<Frame>) -> Result<Vec<Frame>, Error> {
+    let (start, step, count) = stack.into_iter().collect_tuple().unwrap();
+    let (start, step) = (scalar(start)?, scalar(step)?);
+    let Frame::Num(count) = count else {return Error::OpType.into()};
+
+    let elem = match (start.elem_type(), step.elem_type()) {
+        ("complex", _) | (_, "complex") => "complex",
+        ("float", _) | (_, "float") => "float",
+        _ if step.is_float() => "float32",
+        (elem, _) => elem,
+    };
+    let (start, step) = (start.convert(elem)?, step.convert(elem)?);
+    let index = Num::USize((0..from_num(count)?).map(Value).collect_vec().into());
+    Ok(vec![((index.convert(elem)?*step)?+start)?.into()])
+}
+pub const RAMP: NaryOp = NaryOp::new("ramp", framp, 3);
+
+/// start stop count linspace: count floats evenly from start to stop.
+fn flinspace(stack: Vec<Frame>) -> Result<Vec<Frame>, Error> {
+    let (start, stop, count) = stack.into_iter().collect_tuple().unwrap();
+    let (Num::Float(Scalar(start)), Num::Float(Scalar(stop))) =
+        (scalar(start)?.convert("float")?, scalar(stop)?.convert("float")?)
+    else {
+        return Error::OpType.into()
+    };
+    let Frame::Num(count) = count else {return Error::OpType.into()};
+
+    let count = from_num(count)?;
+    let values = (0..count).map(|index| match (start, stop) {
+        (Value(start), Value(_)) if index == 0 => Value(start),
+        (Value(_), Value(stop)) if index == count-1 => Value(stop),
+        (Value(start), Value(stop)) =>
+            Value(start + (stop-start)*index as f64/(count-1) as f64),
+        _ => NaN,
+    });
+    Ok(vec![Num::Float(values.collect_vec().into()).into()])
+}
+pub const LINSPACE: NaryOp = NaryOp::new("linspace", flinspace, 3);
+
+/// value count fill
+fn ffill(stack: Vec<Frame>) -> Result<Vec<Frame>, Error> {
+    let (value, count) = stack.into_iter().collect_tuple().unwrap();
+    let Frame::Num(count) = count else {return Error::OpType.into()};
+    Ok(vec![scalar(value)?.fill(from_num(count)?)?.into()])
+}
+pub const FILL: NaryOp = NaryOp::new("fill", ffill, 2);
+
+/// count elem filled: count copies of value in the element type
+/// named by elem, as for cvt.
+fn filled(stack: Vec<Frame>, value: Num) -> Result<Vec<Frame>, Error> {
+    let (count, name) = stack.into_iter().collect_tuple().unwrap();
+    let Frame::Num(count) = count else {return Error::OpType.into()};
+    Ok(vec![value.convert(&elem(name)?)?.fill(from_num(count)?)?.into()])
+}
+
+fn fzeros(stack: Vec<Frame>) -> Result<Vec<Frame>, Error> {
+    filled(stack, Num::Int(Scalar(Value(0))))
+}
+pub const ZEROS: NaryOp = NaryOp::new("zeros", fzeros, 2);
+
+fn fones(stack: Vec<Frame>) -> Result<Vec<Frame>, Error> {
+    filled(stack, Num::Int(Scalar(Value(1))))
+}
+pub const ONES: NaryOp = NaryOp::new("ones", fones, 2);
+
+fn fnans(stack: Vec<Frame>) -> Result<Vec<Frame>, Error> {
+    filled(stack, Num::Int(Scalar(NaN)))
+}
+pub const NANS: NaryOp = NaryOp::new("nans", fnans, 2);
+
+/// a b concat: the elements of a then of b, in the element type of a;
+/// scalars are single elements.
+fn fconcat(stack: Vec<Frame>) -> Result<Vec<Frame>, Error> {
+    let (Frame::Num(lhs), Frame::Num(rhs)) = stack.into_iter().collect_tuple().unwrap() else {
+        return Error::OpType.into()
+    };
+    Ok(vec![lhs.concat(rhs)?.into()])
+}
+pub const CONCAT: NaryOp = NaryOp::new("concat", fconcat, 2);
+
+/// [ a b ... ] cat: concat over a list, in the element type of its first.
+fn fcat(mut stack: Vec<Frame>) -> Result<Vec<Frame>, Error> {
+    let Frame::Passive(Passive::List(list)) = stack.pop().unwrap() else {
+        return Error::OpType.into()
+    };
+
+    let mut nums = (0..list.len()?).map(|index| match list.get(index)? {
+        Frame::Num(num) => Ok(num),
+        _ => Error::OpType.into(),
+    });
+    let Some(first) = nums.next() else {
+        return Error::OpType.into()
+    };
+    // tiled once, a scalar first is an array
+    let num = nums.try_fold(first?.tile(1)?, |num, next| num.concat(next?))?;
+    Ok(vec![num.into()])
+}
+pub const CAT: NaryOp = NaryOp::new("cat", fcat, 1);
+
+fn freverse(mut stack: Vec<Frame>) -> Result<Vec<Frame>, Error> {
+    let Frame::Num(num) = stack.pop().unwrap() else {
+        return Error::OpType.into()
+    };
+    Ok(vec![num.reverse()?.into()])
+}
+pub const REVERSE: NaryOp = NaryOp::new("reverse", freverse, 1);
+
+fn counted(stack: Vec<Frame>, f: fn(&Num, usize) -> Result<Num, Error>) -> Result<Vec<Frame>, Error> {
+    let (Frame::Num(num), Frame::Num(count)) = stack.into_iter().collect_tuple().unwrap() else {
+        return Error::OpType.into()
+    };
+    Ok(vec![f(&num, from_num(count)?)?.into()])
+}
+
+/// a count replicate: each element count times over; the name repeat
+/// is the control operator's.
+fn freplicate(stack: Vec<Frame>) -> Result<Vec<Frame>, Error> {counted(stack, Num::repeat)}
+pub const REPLICATE: NaryOp = NaryOp::new("replicate", freplicate, 2);
+
+/// a count tile: the whole of a count times over.
+fn ftile(stack: Vec<Frame>) -> Result<Vec<Frame>, Error> {counted(stack, Num::tile)}
+pub const TILE: NaryOp = NaryOp::new("tile", ftile, 2);
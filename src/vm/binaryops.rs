@@ -1,4 +1,6 @@
 use super::optypes::BinaryOp;
+use super::Num;
+use crate::numeric::ops_defs::*;
 
 pub const ADD: BinaryOp = BinaryOp::new("add", |a, b| a+b);
 
@@ -8,3 +10,14 @@ pub const MUL: BinaryOp = BinaryOp::new("mul", |a, b| a*b);
 
 pub const DIV: BinaryOp = BinaryOp::new("div", |a, b| a/b);
 
+pub const ATAN2: BinaryOp = BinaryOp::new("atan2", Num::apply_dyadic::<Atan2Op>);
+
+pub const POW: BinaryOp = BinaryOp::new("pow", Num::apply_dyadic::<PowOp>);
+
+pub const MOD: BinaryOp = BinaryOp::new("mod", Num::apply_dyadic::<ModOp>);
+
+pub const REM: BinaryOp = BinaryOp::new("rem", Num::apply_dyadic::<RemOp>);
+
+pub const MIN: BinaryOp = BinaryOp::new("min", Num::apply_dyadic::<MinOp>);
+
+pub const MAX: BinaryOp = BinaryOp::new("max", Num::apply_dyadic::<MaxOp>);
@@ -0,0 +1,77 @@
+use super::*;
+use crate::error::Error;
+use super::optypes::{NaryOp, VmOp};
+
+/// The operator name finds, if it finds one, bound to where the name was
+/// read, if it was.
+fn operator(name: Name, source: Option<Rc<Source>>, vm: &mut Vm) -> Option<Frame> {
+    match (vm.find(name), source) {
+        (Ok(Frame::Bound(bound)), Some(source)) => Some(Bound {source, ..bound}.into()),
+        (Ok(op), Some(source)) if op.is_op() => Some(Bound {op: Box::new(op), source}.into()),
+        (Ok(op), _) if op.is_op() => Some(op),
+        _ => None,
+    }
+}
+
+/// Replaces the names in list that now find operators with the operators,
+/// bound to where the names were read, if they were, binding writable
+/// procedures within it and making them read-only.
+fn bind(list: &mut List, vm: &mut Vm, pending: &mut Vec<List>) -> Result<(), Error> {
+    if !list.writable() || pending.contains(list) {
+        return Ok(())
+    };
+
+    pending.push(list.clone());
+    for index in 0..list.len()? {
+        let frame = match list.get(index)? {
+            Frame::Active(Active::Name(name, source)) => match operator(name, source, vm) {
+                Some(op) => op,
+                None => continue,
+            },
+            Frame::Active(Active::List(mut proc)) => {
+                bind(&mut proc, vm, pending)?;
+                Active::List(proc.readonly()).into()
+            },
+            _ => continue,
+        };
+        vm.keep(list)?;
+        if let Some(err) = list.put(index, frame) {
+            return err.into()
+        };
+    };
+    pending.pop();
+    Ok(())
+}
+
+fn fbind(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let Frame::Active(Active::List(mut list)) = stack[0].clone() else {
+        return Error::OpType.into()
+    };
+
+    bind(&mut list, vm, &mut Vec::new())?;
+    Ok(vec![Active::List(list).into()])
+}
+pub const BIND: VmOp = VmOp::new("bind", fbind, 1);
+
+fn freadonly(mut stack: Vec<Frame>) -> Result<Vec<Frame>, Error> {
+    let frame = match stack.pop().unwrap() {
+        Frame::Active(Active::List(list))   => Active::List(list.readonly()).into(),
+        Frame::Passive(Passive::List(list)) => Passive::List(list.readonly()).into(),
+        Frame::Dict(dict)                   => dict.readonly().into(),
+        Frame::Num(num)                     => num.readonly()?.into(),
+        _ => return Error::OpType.into(),
+    };
+    Ok(vec![frame])
+}
+pub const READONLY: NaryOp = NaryOp::new("readonly", freadonly, 1);
+
+fn fwcheck(mut stack: Vec<Frame>) -> Result<Vec<Frame>, Error> {
+    let writable = match stack.pop().unwrap() {
+        Frame::Active(Active::List(list)) | Frame::Passive(Passive::List(list)) => list.writable(),
+        Frame::Dict(dict) => dict.writable(),
+        Frame::Num(num) => num.writable()?,
+        _ => return Error::OpType.into(),
+    };
+    Ok(vec![Bool::from(writable).into()])
+}
+pub const WCHECK: NaryOp = NaryOp::new("wcheck", fwcheck, 1);
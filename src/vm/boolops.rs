@@ -0,0 +1,63 @@
+use itertools::Itertools;
+
+use super::*;
+use crate::error::Error;
+use super::optypes::NaryOp;
+use crate::numeric::ops::CompareOp;
+use crate::numeric::ops_defs::*;
+
+fn compare<D: CompareOp>(substack: Vec<Frame>) -> Result<Vec<Frame>, Error> {
+    let (Frame::Num(lhs), Frame::Num(rhs)) = substack.into_iter().collect_tuple().unwrap()
+    else {
+        return Error::OpType.into()
+    };
+    Ok(vec![lhs.apply_compare::<D>(rhs)?.into()])
+}
+pub const LT: NaryOp = NaryOp::new("lt", compare::<LtOp>, 2);
+pub const LE: NaryOp = NaryOp::new("le", compare::<LeOp>, 2);
+pub const GT: NaryOp = NaryOp::new("gt", compare::<GtOp>, 2);
+pub const GE: NaryOp = NaryOp::new("ge", compare::<GeOp>, 2);
+
+fn equal(substack: Vec<Frame>, negate: bool) -> Result<Vec<Frame>, Error> {
+    let result = match substack.into_iter().collect_tuple().unwrap() {
+        (Frame::Num(lhs), Frame::Num(rhs)) => lhs.apply_compare::<EqOp>(rhs)?,
+        (Frame::Bool(lhs), Frame::Bool(rhs)) =>
+            Bool::zip_with(lhs, rhs, |lhs, rhs| Some(lhs? == rhs?))?,
+        (lhs, rhs) => Bool::from(lhs == rhs),
+    };
+    let result = if negate {!result} else {result};
+    Ok(vec![result.into()])
+}
+
+fn feq(substack: Vec<Frame>) -> Result<Vec<Frame>, Error> {equal(substack, false)}
+pub const EQ: NaryOp = NaryOp::new("eq", feq, 2);
+
+fn fne(substack: Vec<Frame>) -> Result<Vec<Frame>, Error> {equal(substack, true)}
+pub const NE: NaryOp = NaryOp::new("ne", fne, 2);
+
+fn logical(substack: Vec<Frame>, op: fn(Bool, Bool) -> Result<Bool, Error>)
+    -> Result<Vec<Frame>, Error>
+{
+    let (Frame::Bool(lhs), Frame::Bool(rhs)) = substack.into_iter().collect_tuple().unwrap()
+    else {
+        return Error::OpType.into()
+    };
+    Ok(vec![op(lhs, rhs)?.into()])
+}
+
+fn logic_and(substack: Vec<Frame>) -> Result<Vec<Frame>, Error> {logical(substack, Bool::and)}
+pub const AND: NaryOp = NaryOp::new("and", logic_and, 2);
+
+fn logic_or(substack: Vec<Frame>) -> Result<Vec<Frame>, Error> {logical(substack, Bool::or)}
+pub const OR: NaryOp = NaryOp::new("or", logic_or, 2);
+
+fn logic_xor(substack: Vec<Frame>) -> Result<Vec<Frame>, Error> {logical(substack, Bool::xor)}
+pub const XOR: NaryOp = NaryOp::new("xor", logic_xor, 2);
+
+fn fnot(mut substack: Vec<Frame>) -> Result<Vec<Frame>, Error> {
+    let Frame::Bool(b) = substack.pop().unwrap() else {
+        return Error::OpType.into()
+    };
+    Ok(vec![(!b).into()])
+}
+pub const NOT: NaryOp = NaryOp::new("not", fnot, 1);
@@ -0,0 +1,309 @@
+use std::fmt;
+use std::rc::Rc;
+
+use super::*;
+use crate::error::Error;
+use super::optypes::{VmOp, Op};
+
+/// One step of compiled code; the operands index the frames and
+/// branches of the code, or are instruction targets.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Instr {
+    /// Pushes the frame onto the operand stack.
+    Push(u32),
+    /// Runs the operator.
+    Op(u32),
+    /// Looks up the name and executes what it finds.
+    Name(u32),
+    /// Executes any other frame, as the execution stack would.
+    Exec(u32),
+    /// Pops a boolean scalar, falling through on true and jumping to the
+    /// branch's `otherwise` on false; anything else is left to the
+    /// branch's operator, with its procedures.
+    Branch(u32),
+    Jump(u32),
+}
+
+/// An inlined `proc if` or `proc proc ifelse`.
+#[derive(Debug)]
+struct Branch {
+    op: Frame,
+    procs: Vec<Frame>,
+    otherwise: u32,
+    end: u32,
+}
+
+#[derive(Debug)]
+struct Compiled {
+    list: List,
+    instrs: Vec<Instr>,
+    frames: Vec<Frame>,
+    branches: Vec<Branch>,
+}
+
+/// A procedure compiled to instructions, from pc on: the rest of a
+/// running procedure is left on the execution stack as its code with
+/// pc past the instruction being run.
+/// The code is a snapshot of the procedure as it was compiled, which
+/// its list, read-only, shows.
+#[derive(Debug, Clone)]
+pub struct Code {
+    compiled: Rc<Compiled>,
+    pc: usize,
+}
+
+impl PartialEq for Code {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.compiled, &other.compiled) && self.pc == other.pc
+    }
+}
+
+impl fmt::Display for Code {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.compiled.list)
+    }
+}
+
+fn is_vmop(frame: &Frame, op: VmOp) -> bool {
+    match frame {
+        Frame::VmOp(frame) => frame.name() == op.name(),
+        Frame::Bound(bound) => is_vmop(&bound.op, op),
+        _ => false,
+    }
+}
+
+fn contents(list: &List) -> Result<Vec<Frame>, Error> {
+    (0..list.len()?).map(|index| list.get(index)).collect()
+}
+
+#[derive(Default)]
+struct Compiler {
+    instrs: Vec<Instr>,
+    frames: Vec<Frame>,
+    branches: Vec<Branch>,
+    pending: Vec<List>,
+}
+
+impl Compiler {
+    fn pc(&self) -> u32 {
+        self.instrs.len() as u32
+    }
+
+    fn frame(&mut self, frame: Frame) -> u32 {
+        self.frames.push(frame);
+        (self.frames.len()-1) as u32
+    }
+
+    /// Compiles list as it is now, with the procedures in it compiled unless
+    /// they contain it; what was compiled is kept as a new read-only list.
+    /// Names are looked up as they run, so only those bind has already
+    /// replaced run as operators.
+    fn compile(&mut self, list: &List, vm: &mut Vm) -> Result<Code, Error> {
+        let outer = std::mem::take(self);
+        self.pending = outer.pending.clone();
+        self.pending.push(list.clone());
+
+        let mut frames = contents(list)?;
+        for frame in &mut frames {
+            match frame {
+                // procedures nested in a procedure are data,
+                // compiled unless they contain the procedure itself
+                Frame::Active(Active::List(list)) if !self.pending.contains(list) => {
+                    let code = self.compile(&list.clone(), vm)?;
+                    *frame = Active::Code(code).into()
+                },
+                _ => (),
+            }
+        };
+        self.block(&frames)?;
+
+        let inner = std::mem::replace(self, outer);
+        let Some(csave) = vm.save_stack.last_mut() else {
+            panic!("save stack is empty")
+        };
+        let compiled = Compiled {
+            list: csave.put(frames)?.readonly(),
+            instrs: inner.instrs,
+            frames: inner.frames,
+            branches: inner.branches,
+        };
+        Ok(Code {compiled: Rc::new(compiled), pc: 0})
+    }
+
+    fn block(&mut self, frames: &[Frame]) -> Result<(), Error> {
+        let mut index = 0;
+        while index < frames.len() {
+            if let Some(used) = self.branch(&frames[index..])? {
+                index += used;
+                continue
+            };
+
+            let frame = frames[index].clone();
+            let instr = match frame {
+                Frame::Active(Active::List(_) | Active::Code(_)) => Instr::Push(self.frame(frame)),
+                Frame::Active(Active::Name(..)) => Instr::Name(self.frame(frame)),
+                Frame::Active(_) | Frame::Control(_) => Instr::Exec(self.frame(frame)),
+                ref op if op.is_op() => Instr::Op(self.frame(frame)),
+                _ => Instr::Push(self.frame(frame)),
+            };
+            self.instrs.push(instr);
+            index += 1;
+        };
+        Ok(())
+    }
+
+    /// Inlines `proc if` and `proc proc ifelse` where the operators
+    /// themselves, rather than their names, follow the compiled procedures;
+    /// the number of frames used, if it did.
+    fn branch(&mut self, frames: &[Frame]) -> Result<Option<usize>, Error> {
+        let (codes, used) = match frames {
+            [Frame::Active(Active::Code(then)), op, ..] if is_vmop(op, controlops::IF) =>
+                (vec![then.clone()], 2),
+            [Frame::Active(Active::Code(then)), Frame::Active(Active::Code(other)), op, ..]
+                if is_vmop(op, controlops::IFELSE) => (vec![then.clone(), other.clone()], 3),
+            _ => return Ok(None),
+        };
+        let procs = frames[..used-1].to_vec();
+
+        let branch = self.branches.len();
+        self.branches.push(Branch {op: frames[used-1].clone(), procs, otherwise: 0, end: 0});
+        self.instrs.push(Instr::Branch(branch as u32));
+
+        self.block(&contents(codes[0].list())?)?;
+        let jump = self.instrs.len();
+        if codes.len() > 1 {
+            self.instrs.push(Instr::Jump(0))
+        };
+        let otherwise = self.pc();
+        if let Some(other) = codes.get(1) {
+            self.block(&contents(other.list())?)?
+        };
+        let end = self.pc();
+        if codes.len() > 1 {
+            self.instrs[jump] = Instr::Jump(end)
+        };
+
+        self.branches[branch].otherwise = otherwise;
+        self.branches[branch].end = end;
+        Ok(Some(used))
+    }
+}
+
+impl Code {
+    pub fn list(&self) -> &List {
+        &self.compiled.list
+    }
+
+    /// The frame of the last instruction run: for the rest of a running
+    /// procedure, as for a list, what called whatever is running above it.
+    pub(crate) fn previous(&self) -> Option<Frame> {
+        let index = match self.compiled.instrs.get(self.pc.checked_sub(1)?)? {
+            Instr::Op(index) | Instr::Name(index) | Instr::Exec(index) => *index,
+            _ => return None,
+        };
+        Some(self.compiled.frames[index as usize].clone())
+    }
+
+    /// Runs f with the rest of the code, from pc, under anything it hands
+    /// to the execution stack; true if it handed nothing, so the rest
+    /// can be run straight away.
+    fn around(&self, pc: usize, vm: &mut Vm, f: impl FnOnce(&mut Vm) -> Result<(), Error>)
+        -> Result<bool, Error>
+    {
+        if pc >= self.compiled.instrs.len() {
+            f(vm)?;
+            return Ok(false)
+        };
+
+        let rest = Code {compiled: self.compiled.clone(), pc};
+        let frame: Frame = Active::Code(rest).into();
+        vm.exec_stack.push(frame.clone());
+        let len = vm.exec_stack.len();
+        f(vm)?;
+        if vm.exec_stack.len() == len && vm.exec_stack.last() == Some(&frame) {
+            vm.exec_stack.pop();
+            return Ok(true)
+        };
+        Ok(false)
+    }
+
+    /// Runs instructions until one hands something to the execution stack.
+    pub(crate) fn step(self, vm: &mut Vm) -> Result<(), Error> {
+        let compiled = &*self.compiled;
+        let mut pc = self.pc;
+        while let Some(instr) = compiled.instrs.get(pc) {
+            pc += 1;
+            let go_on = match *instr {
+                Instr::Push(index) => {
+                    vm.op_stack.push(compiled.frames[index as usize].clone());
+                    true
+                },
+                Instr::Jump(target) => {
+                    pc = target as usize;
+                    true
+                },
+                Instr::Op(index) | Instr::Exec(index) => {
+                    let frame = compiled.frames[index as usize].clone();
+                    self.around(pc, vm, |vm| vm.step(frame))?
+                },
+                // frames are data while a procedure is being built
+                Instr::Name(index) if vm.proc_depth > 0 => {
+                    vm.step(compiled.frames[index as usize].clone())?;
+                    true
+                },
+                Instr::Branch(index) if vm.proc_depth > 0 => {
+                    let branch = &compiled.branches[index as usize];
+                    for frame in branch.procs.iter().chain([&branch.op]) {
+                        vm.step(frame.clone())?
+                    };
+                    pc = branch.end as usize;
+                    true
+                },
+                Instr::Name(index) => {
+                    let Frame::Active(Active::Name(name, source))
+                        = &compiled.frames[index as usize]
+                    else {
+                        unreachable!()
+                    };
+                    if source.is_some() {
+                        vm.location = source.clone()
+                    };
+                    self.around(pc, vm, |vm| match vm.find(name.clone()) {
+                        Ok(frame) => vm.step(frame),
+                        Err(error) => vm.signal(error, name.clone(), 0),
+                    })?
+                },
+                Instr::Branch(index) => {
+                    let branch = &compiled.branches[index as usize];
+                    match vm.op_stack.last() {
+                        Some(Frame::Bool(Bool::Scalar(Some(truth)))) => {
+                            if !*truth {
+                                pc = branch.otherwise as usize
+                            };
+                            vm.op_stack.pop();
+                            true
+                        },
+                        _ => {
+                            vm.op_stack.extend(branch.procs.iter().cloned());
+                            pc = branch.end as usize;
+                            let op = branch.op.clone();
+                            self.around(pc, vm, |vm| vm.step(op))?
+                        },
+                    }
+                },
+            };
+            if !go_on {break};
+        };
+        Ok(())
+    }
+}
+
+fn fcompile(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let code = match stack[0].clone() {
+        Frame::Active(Active::List(list)) => Compiler::default().compile(&list, vm)?,
+        Frame::Active(Active::Code(code)) => code,
+        _ => return Error::OpType.into(),
+    };
+    Ok(vec![Active::Code(code).into()])
+}
+pub const COMPILE: VmOp = VmOp::new("compile", fcompile, 1);
@@ -0,0 +1,11 @@
+use super::optypes::{UnaryOp, BinaryOp};
+use super::Num;
+use crate::numeric::complex::Complex;
+
+pub const RE: UnaryOp   = UnaryOp::new("re",   |a| a.complex_part(Complex::re));
+pub const IM: UnaryOp   = UnaryOp::new("im",   |a| a.complex_part(Complex::im));
+pub const ARG: UnaryOp  = UnaryOp::new("arg",  |a| a.complex_part(Complex::arg));
+pub const CONJ: UnaryOp = UnaryOp::new("conj", |a| Ok(a.conj()));
+
+/// r theta polar
+pub const POLAR: BinaryOp = BinaryOp::new("polar", Num::polar);
@@ -0,0 +1,186 @@
+use std::fmt;
+use std::cmp::Ordering;
+
+use itertools::Itertools;
+
+use super::*;
+use crate::error::Error;
+use super::optypes::VmOp;
+use super::naryops::from_num;
+use crate::numeric::{Scalar, Value};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Control {
+    Repeat {count: usize, proc: Active},
+    For {counter: Num, incr: Num, limit: Num, down: bool, proc: Active},
+    Loop {proc: Active},
+    Stopped,
+}
+
+impl From<Control> for Frame {
+    fn from(item: Control) -> Self {
+        Frame::Control(item)
+    }
+}
+
+impl fmt::Display for Control {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Control::Repeat {..} => write!(f, "--repeat--"),
+            Control::For {..}    => write!(f, "--for--"),
+            Control::Loop {..}   => write!(f, "--loop--"),
+            Control::Stopped     => write!(f, "--stopped--"),
+        }
+    }
+}
+
+impl Control {
+    pub(crate) fn proc(&self) -> Option<&Active> {
+        match self {
+            Control::Repeat {proc, ..}
+            | Control::For {proc, ..}
+            | Control::Loop {proc} => Some(proc),
+            Control::Stopped => None,
+        }
+    }
+
+    pub(crate) fn step(self, vm: &mut Vm) -> Result<(), Error> {
+        match self {
+            Control::Repeat {count: 0, ..} => (),
+            Control::Repeat {count, proc} => {
+                vm.exec_stack.push(Control::Repeat {count: count-1, proc: proc.clone()}.into());
+                vm.exec_stack.push(proc.into());
+            },
+            Control::For {counter, incr, limit, down, proc} => {
+                let done = match counter.compare(&limit)? {
+                    None                    => true,
+                    Some(Ordering::Greater) => !down,
+                    Some(Ordering::Less)    => down,
+                    Some(Ordering::Equal)   => false,
+                };
+                if !done {
+                    let next = (counter.clone() + incr.clone())?;
+                    vm.exec_stack.push(Control::For {
+                        counter: next, incr, limit, down, proc: proc.clone()
+                    }.into());
+                    vm.exec_stack.push(proc.into());
+                    vm.op_stack.push(counter.into());
+                }
+            },
+            Control::Loop {proc} => {
+                vm.exec_stack.push(Control::Loop {proc: proc.clone()}.into());
+                vm.exec_stack.push(proc.into());
+            },
+            Control::Stopped => vm.op_stack.push(Bool::from(false).into()),
+        };
+        Ok(())
+    }
+}
+
+fn truth(frame: Frame) -> Result<bool, Error> {
+    match frame {
+        Frame::Bool(Bool::Scalar(Some(truth))) => Ok(truth),
+        Frame::Bool(Bool::Scalar(None)) => Error::IllNan.into(),
+        Frame::Num(num) => match num.compare(&Num::Int(Scalar(Value(0))))? {
+            None => Error::IllNan.into(),
+            Some(ordering) => Ok(ordering != Ordering::Equal),
+        },
+        _ => Error::OpType.into(),
+    }
+}
+
+fn proc(frame: Frame) -> Result<Active, Error> {
+    let Frame::Active(active) = frame else {
+        return Error::OpType.into()
+    };
+    Ok(active)
+}
+
+fn fif(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let (cond, body) = stack.iter().cloned().collect_tuple().unwrap();
+    let body = proc(body)?;
+    if truth(cond)? {
+        vm.exec_stack.push(body.into())
+    };
+    Ok(vec![])
+}
+pub const IF: VmOp = VmOp::new("if", fif, 2);
+
+fn fifelse(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let (cond, then, other) = stack.iter().cloned().collect_tuple().unwrap();
+    let (then, other) = (proc(then)?, proc(other)?);
+    let body = if truth(cond)? {then} else {other};
+    vm.exec_stack.push(body.into());
+    Ok(vec![])
+}
+pub const IFELSE: VmOp = VmOp::new("ifelse", fifelse, 3);
+
+fn frepeat(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let (Frame::Num(count), body) = stack.iter().cloned().collect_tuple().unwrap() else {
+        return Error::OpType.into()
+    };
+    let proc = proc(body)?;
+    vm.exec_stack.push(Control::Repeat {count: from_num(count)?, proc}.into());
+    Ok(vec![])
+}
+pub const REPEAT: VmOp = VmOp::new("repeat", frepeat, 2);
+
+fn ffor(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let (Frame::Num(counter), Frame::Num(incr), Frame::Num(limit), body)
+        = stack.iter().cloned().collect_tuple().unwrap()
+    else {
+        return Error::OpType.into()
+    };
+    let proc = proc(body)?;
+    // as in PostScript, the counter is a float if any operand is
+    let counter = if [&counter, &incr, &limit].iter().any(|num| num.is_float()) {
+        counter.convert("float")?
+    } else {counter};
+
+    let Some(direction) = incr.compare(&Num::Int(Scalar(Value(0))))? else {
+        return Error::IllNan.into()
+    };
+    if counter.compare(&limit)?.is_none() {
+        return Error::IllNan.into()
+    };
+
+    let down = direction == Ordering::Less;
+    vm.exec_stack.push(Control::For {counter, incr, limit, down, proc}.into());
+    Ok(vec![])
+}
+pub const FOR: VmOp = VmOp::new("for", ffor, 4);
+
+fn floop(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let proc = proc(stack[0].clone())?;
+    vm.exec_stack.push(Control::Loop {proc}.into());
+    Ok(vec![])
+}
+pub const LOOP: VmOp = VmOp::new("loop", floop, 1);
+
+fn fexit(_: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let base = vm.exec_base;
+    let Some(index) = vm.exec_stack[base..].iter()
+        .rposition(|frame| matches!(frame, Frame::Control(_)))
+    else {
+        return Error::InvalidExit.into()
+    };
+    if let Frame::Control(Control::Stopped) = vm.exec_stack[base+index] {
+        return Error::InvalidExit.into()
+    };
+    vm.exec_stack.truncate(base+index);
+    Ok(vec![])
+}
+pub const EXIT: VmOp = VmOp::new("exit", fexit, 0);
+
+fn fstopped(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let proc = proc(stack[0].clone())?;
+    vm.exec_stack.push(Control::Stopped.into());
+    vm.exec_stack.push(proc.into());
+    Ok(vec![])
+}
+pub const STOPPED: VmOp = VmOp::new("stopped", fstopped, 1);
+
+fn fstop(_: &[Frame], _: &mut Vm) -> Result<Vec<Frame>, Error> {
+    Error::Stop.into()
+}
+pub const STOP: VmOp = VmOp::new("stop", fstop, 0);
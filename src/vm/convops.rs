@@ -0,0 +1,58 @@
+use itertools::Itertools;
+
+use super::*;
+use crate::error::Error;
+use super::optypes::{UnaryOp, VmOp};
+
+pub const CVI: UnaryOp = UnaryOp::new("cvi", |a| a.to::<i64>());
+pub const CVD: UnaryOp = UnaryOp::new("cvd", |a| a.to::<f64>());
+pub const CVU: UnaryOp = UnaryOp::new("cvu", |a| a.to::<usize>());
+pub const CVB: UnaryOp = UnaryOp::new("cvb", |a| a.to::<u8>());
+pub const CVS: UnaryOp = UnaryOp::new("cvs", |a| a.to::<i32>());
+pub const CVF: UnaryOp = UnaryOp::new("cvf", |a| a.to::<f32>());
+pub const CVQ: UnaryOp = UnaryOp::new("cvq", |a| a.to::<i128>());
+
+/// The element type named, either alone or as type reports it, with a
+/// cardinality that must be the frame's.
+fn elem<'a>(name: &'a str, frame: &Frame) -> Result<&'a str, Error> {
+    let cardinality = match frame {
+        Frame::Num(num) => num.cardinality(),
+        Frame::Bool(Bool::Scalar(_)) => "scalar",
+        Frame::Bool(Bool::Array(_)) => "array",
+        _ => return Error::OpType.into(),
+    };
+    for suffix in ["scalar", "array"] {
+        if let Some(elem) = name.strip_suffix(suffix) {
+            if suffix != cardinality {return Error::OpType.into()};
+            return Ok(elem)
+        }
+    };
+    Ok(name)
+}
+
+/// Converts numbers to the element type named, or to bool, being false
+/// for zero; booleans convert to ones and zeros.
+fn fcvt(stack: &[Frame], _: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let (frame, Frame::Passive(Passive::Name(name)))
+        = stack.iter().cloned().collect_tuple().unwrap()
+    else {
+        return Error::OpType.into()
+    };
+
+    let name: &String = name.borrow();
+    let elem = elem(name, &frame)?;
+    match (frame, elem) {
+        (Frame::Num(num), "bool") => Ok(vec![num.truth().into()]),
+        (Frame::Num(num), elem) => Ok(vec![num.convert(elem)?.into()]),
+        (Frame::Bool(truth), "bool") => Ok(vec![truth.into()]),
+        (Frame::Bool(truth), elem) => Ok(vec![Num::from_truth(&truth, elem)?.into()]),
+        _ => Error::OpType.into(),
+    }
+}
+pub const CVT: VmOp = VmOp::new("cvt", fcvt, 2);
+
+fn ftype(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let name = vm.intern(stack[0].type_name());
+    Ok(vec![Passive::Name(name).into()])
+}
+pub const TYPE: VmOp = VmOp::new("type", ftype, 1);
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use super::*;
+use crate::error::Error;
+use super::optypes::VmOp;
+use super::naryops::from_num;
+use crate::numeric::Value;
+
+fn fdict(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let Frame::Num(n) = stack[0].clone() else {
+        return Error::OpType.into()
+    };
+
+    let cap = from_num(n)?;
+    let Some(csave) = vm.save_stack.last_mut() else {
+        panic!("save stack is empty")
+    };
+    let dict = csave.put(HashMap::<Name, Frame>::with_capacity(cap))?;
+    Ok(vec![dict.into()])
+}
+pub const DICT: VmOp = VmOp::new("dict", fdict, 1);
+
+fn fbegin(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let Frame::Dict(dict) = stack[0].clone() else {
+        return Error::OpType.into()
+    };
+
+    vm.dict_stack.push_front(dict);
+    Ok(vec![])
+}
+pub const BEGIN: VmOp = VmOp::new("begin", fbegin, 1);
+
+fn fend(_: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    if vm.dict_stack.len() <= 1 {
+        return Error::DictStackUnderflow.into()
+    };
+
+    vm.dict_stack.pop_front();
+    Ok(vec![])
+}
+pub const END: VmOp = VmOp::new("end", fend, 0);
+
+fn fdef(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let (Frame::Passive(Passive::Name(name)), frame)
+        = stack.iter().cloned().collect_tuple().unwrap()
+    else {
+        return Error::OpType.into()
+    };
+
+    let Some(mut dict) = vm.dict_stack.front().cloned() else {
+        panic!("dict_stack empty")
+    };
+    vm.keep(&dict)?;
+    if let Some(err) = dict.put(name, frame) {
+        return err.into()
+    };
+    Ok(vec![])
+}
+pub const DEF: VmOp = VmOp::new("def", fdef, 2);
+
+fn fload(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let Frame::Passive(Passive::Name(name)) = stack[0].clone() else {
+        return Error::OpType.into()
+    };
+
+    Ok(vec![vm.find(name)?])
+}
+pub const LOAD: VmOp = VmOp::new("load", fload, 1);
+
+fn fknown(stack: &[Frame], _: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let (Frame::Dict(dict), Frame::Passive(Passive::Name(name)))
+        = stack.iter().cloned().collect_tuple().unwrap()
+    else {
+        return Error::OpType.into()
+    };
+
+    Ok(vec![Bool::from(dict.find(&name)?.is_some()).into()])
+}
+pub const KNOWN: VmOp = VmOp::new("known", fknown, 2);
+
+fn fwhere(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let Frame::Passive(Passive::Name(name)) = stack[0].clone() else {
+        return Error::OpType.into()
+    };
+
+    for dict in &vm.dict_stack {
+        if dict.find(&name)?.is_some() {
+            return Ok(vec![dict.clone().into(), Bool::from(true).into()])
+        }
+    };
+    Ok(vec![Bool::from(false).into()])
+}
+pub const WHERE: VmOp = VmOp::new("where", fwhere, 1);
+
+fn fundef(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let (Frame::Dict(mut dict), Frame::Passive(Passive::Name(name)))
+        = stack.iter().cloned().collect_tuple().unwrap()
+    else {
+        return Error::OpType.into()
+    };
+
+    vm.keep(&dict)?;
+    if let Some(err) = dict.remove(&name) {
+        return err.into()
+    };
+    Ok(vec![])
+}
+pub const UNDEF: VmOp = VmOp::new("undef", fundef, 2);
+
+fn fcurrentdict(_: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let Some(dict) = vm.dict_stack.front() else {
+        panic!("dict_stack empty")
+    };
+    Ok(vec![dict.clone().into()])
+}
+pub const CURRENTDICT: VmOp = VmOp::new("currentdict", fcurrentdict, 0);
+
+fn fcountdictstack(_: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    Ok(vec![Num::USize(Scalar(Value(vm.dict_stack.len()))).into()])
+}
+pub const COUNTDICTSTACK: VmOp = VmOp::new("countdictstack", fcountdictstack, 0);
@@ -0,0 +1,167 @@
+use itertools::Itertools;
+
+use super::*;
+use crate::error::Error;
+use super::optypes::VmOp;
+use crate::numeric::Array;
+use crate::numeric::linalg::{self, Matrix, to_float, to_scalar};
+
+// Vectors are numeric arrays and matrices are lists of them as rows,
+// worked on as floats. Results that are not finite are NaN, settled by
+// the numeric policy where every operand was finite.
+
+fn floats(frame: Frame) -> Result<Vec<f64>, Error> {
+    let Frame::Num(num) = frame else {return Error::OpType.into()};
+    match num.convert("float")? {
+        Num::Float(Array(array)) => Ok(array.to_vec().into_iter().map(to_float).collect()),
+        _ => Error::OpType.into(),
+    }
+}
+
+fn matrix(frame: Frame) -> Result<Matrix, Error> {
+    let Frame::Passive(Passive::List(list)) = frame else {
+        return Error::OpType.into()
+    };
+    let rows = (0..list.len()?)
+        .map(|index| floats(list.get(index)?))
+        .collect::<Result<Vec<_>, _>>()?;
+    Matrix::from_rows(rows)
+}
+
+enum Operand {
+    Vector(Vec<f64>),
+    Matrix(Matrix),
+}
+
+impl Operand {
+    fn has_nan(&self) -> bool {
+        match self {
+            Operand::Vector(values) => has_nan(values),
+            Operand::Matrix(matrix) => matrix.has_nan(),
+        }
+    }
+}
+
+fn has_nan(values: &[f64]) -> bool {
+    values.iter().any(|value| !value.is_finite())
+}
+
+fn operand(frame: Frame) -> Result<Operand, Error> {
+    match frame {
+        Frame::Num(_) => Ok(Operand::Vector(floats(frame)?)),
+        _ => Ok(Operand::Matrix(matrix(frame)?)),
+    }
+}
+
+fn vector_frame(values: Vec<f64>, finite: bool) -> Result<Frame, Error> {
+    let values = values.into_iter().map(|value| to_scalar(value, finite)).try_collect::<_, Vec<_>, _>()?;
+    Ok(Num::Float(values.into()).into())
+}
+
+fn matrix_frame(matrix: &Matrix, finite: bool, vm: &mut Vm) -> Result<Frame, Error> {
+    let rows = matrix.to_rows().into_iter()
+        .map(|row| vector_frame(row, finite))
+        .try_collect::<_, Vec<_>, _>()?;
+    let Some(csave) = vm.save_stack.last_mut() else {
+        panic!("save stack is empty")
+    };
+    Ok(Passive::List(csave.put(rows)?).into())
+}
+
+/// The result for a right-hand side b: a vector for a vector.
+fn result_frame(result: Matrix, b: &Operand, finite: bool, vm: &mut Vm) -> Result<Frame, Error> {
+    match b {
+        Operand::Vector(_) => vector_frame(result.transpose().to_rows().concat(), finite),
+        Operand::Matrix(_) => matrix_frame(&result, finite, vm),
+    }
+}
+
+fn rhs(b: &Operand) -> Matrix {
+    match b {
+        Operand::Vector(values) => Matrix::column(values.clone()),
+        Operand::Matrix(matrix) => matrix.clone(),
+    }
+}
+
+/// a b dot: the float sum of the products of the elements.
+fn fdot(stack: &[Frame], _: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let (lhs, rhs) = stack.iter().cloned().collect_tuple().unwrap();
+    let (lhs, rhs) = (floats(lhs)?, floats(rhs)?);
+    let dot = linalg::dot(&lhs, &rhs)?;
+    Ok(vec![Num::Float(Scalar(to_scalar(dot, !has_nan(&lhs) && !has_nan(&rhs))?)).into()])
+}
+pub const DOT: VmOp = VmOp::new("dot", fdot, 2);
+
+/// A B matmul, where a vector is a column on the right and a row on the
+/// left, giving a vector; two vectors give their dot product.
+fn fmatmul(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let (lhs, rhs) = stack.iter().cloned().collect_tuple().unwrap();
+    let (lhs, rhs) = (operand(lhs)?, operand(rhs)?);
+    let finite = !lhs.has_nan() && !rhs.has_nan();
+    let frame = match (lhs, rhs) {
+        (Operand::Vector(lhs), Operand::Vector(rhs)) =>
+            Num::Float(Scalar(to_scalar(linalg::dot(&lhs, &rhs)?, finite)?)).into(),
+        (Operand::Matrix(lhs), Operand::Matrix(rhs)) =>
+            matrix_frame(&lhs.matmul(&rhs)?, finite, vm)?,
+        (Operand::Matrix(lhs), Operand::Vector(rhs)) =>
+            vector_frame(lhs.matmul(&Matrix::column(rhs))?.to_rows().concat(), finite)?,
+        (Operand::Vector(lhs), Operand::Matrix(rhs)) =>
+            vector_frame(Matrix::column(lhs).transpose().matmul(&rhs)?.to_rows().concat(), finite)?,
+    };
+    Ok(vec![frame])
+}
+pub const MATMUL: VmOp = VmOp::new("matmul", fmatmul, 2);
+
+fn fouter(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let (lhs, rhs) = stack.iter().cloned().collect_tuple().unwrap();
+    let (lhs, rhs) = (floats(lhs)?, floats(rhs)?);
+    let finite = !has_nan(&lhs) && !has_nan(&rhs);
+    Ok(vec![matrix_frame(&Matrix::outer(&lhs, &rhs), finite, vm)?])
+}
+pub const OUTER: VmOp = VmOp::new("outer", fouter, 2);
+
+fn ftranspose(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let matrix = matrix(stack[0].clone())?;
+    Ok(vec![matrix_frame(&matrix.transpose(), !matrix.has_nan(), vm)?])
+}
+pub const TRANSPOSE: VmOp = VmOp::new("transpose", ftranspose, 1);
+
+/// A inv: all NaN for a singular matrix.
+fn finv(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let matrix = matrix(stack[0].clone())?;
+    Ok(vec![matrix_frame(&matrix.inv()?, !matrix.has_nan(), vm)?])
+}
+pub const INV: VmOp = VmOp::new("inv", finv, 1);
+
+fn fdet(stack: &[Frame], _: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let matrix = matrix(stack[0].clone())?;
+    Ok(vec![Num::Float(Scalar(to_scalar(matrix.det()?, !matrix.has_nan())?)).into()])
+}
+pub const DET: VmOp = VmOp::new("det", fdet, 1);
+
+/// A b solve: x with A x = b, by LU decomposition with partial pivoting;
+/// all NaN for a singular A.
+fn fsolve(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let (a, b) = stack.iter().cloned().collect_tuple().unwrap();
+    let (a, b) = (matrix(a)?, operand(b)?);
+    let finite = !a.has_nan() && !b.has_nan();
+    result_frame(a.solve(&rhs(&b))?, &b, finite, vm).map(|frame| vec![frame])
+}
+pub const SOLVE: VmOp = VmOp::new("solve", fsolve, 2);
+
+/// A qr: Q R
+fn fqr(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let matrix = matrix(stack[0].clone())?;
+    let (finite, (q, r)) = (!matrix.has_nan(), matrix.qr());
+    Ok(vec![matrix_frame(&q, finite, vm)?, matrix_frame(&r, finite, vm)?])
+}
+pub const QR: VmOp = VmOp::new("qr", fqr, 1);
+
+/// A b lstsq: x minimising |A x - b|.
+fn flstsq(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let (a, b) = stack.iter().cloned().collect_tuple().unwrap();
+    let (a, b) = (matrix(a)?, operand(b)?);
+    let finite = !a.has_nan() && !b.has_nan();
+    result_frame(a.lstsq(&rhs(&b))?, &b, finite, vm).map(|frame| vec![frame])
+}
+pub const LSTSQ: VmOp = VmOp::new("lstsq", flstsq, 2);
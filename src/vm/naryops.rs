@@ -2,7 +2,7 @@ use itertools::Itertools;
 
 use super::*;
 use crate::error::Error;
-use super::optypes::NaryOp;
+use super::optypes::{NaryOp, VmOp};
 use crate::numeric::{Scalar, Value, NumericValue};
 use crate::numeric::primitive::NumericPrimitive;
 
@@ -39,51 +39,109 @@ pub fn from_num(num: Num) -> Result<usize, Error> {
     let index = match num {
         Num::Int(Scalar(i))  => to_index(i)?,
         Num::USize(Scalar(i)) => to_index(i)?,
+        Num::Byte(Scalar(i))  => to_index(i)?,
+        Num::Int32(Scalar(i)) => to_index(i)?,
+        Num::Int128(Scalar(i)) => to_index(i)?,
         _ => return Error::OpType.into(),
     };
     Ok(index)
 }
 
 fn fget(substack: Vec<Frame>) -> Result<Vec<Frame>, Error> {
-    let (Frame::Passive(Passive::List(ref list)), Frame::Num(n))
-        = substack.into_iter().collect_tuple().unwrap()
-    else {return Error::OpType.into()};
-    
-    Ok(vec![list.get(from_num(n)?)?])
+    match substack.into_iter().collect_tuple().unwrap() {
+        (Frame::Passive(Passive::List(ref list)), Frame::Num(n)) =>
+            Ok(vec![list.get(from_num(n)?)?]),
+        (Frame::Num(ref array), Frame::Num(n)) =>
+            Ok(vec![array.get(from_num(n)?)?.into()]),
+        (Frame::Dict(ref dict), Frame::Passive(Passive::Name(name))) =>
+            Ok(vec![dict.get(name)?]),
+        _ => Error::OpType.into(),
+    }
 }
 pub const GET: NaryOp = NaryOp::new("get", fget, 2);
 
-fn fput(substack: Vec<Frame>) -> Result<Vec<Frame>, Error> {
-    let (f1, Frame::Passive(Passive::List(ref mut list)), Frame::Num(n))
-        = substack.into_iter().collect_tuple().unwrap()
-    else {return Error::OpType.into()};
-    
-    if let Some(err) = list.put(from_num(n)?, f1) {
+fn fput(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let error = match stack.iter().cloned().collect_tuple().unwrap() {
+        (f1, Frame::Passive(Passive::List(ref mut list)), Frame::Num(n)) => {
+            vm.keep(list)?;
+            list.put(from_num(n)?, f1)
+        },
+        (Frame::Num(value), Frame::Num(ref mut array), Frame::Num(n)) =>
+            array.put(from_num(n)?, value),
+        (f1, Frame::Dict(ref mut dict), Frame::Passive(Passive::Name(name))) => {
+            vm.keep(dict)?;
+            dict.put(name, f1)
+        },
+        _ => return Error::OpType.into(),
+    };
+
+    if let Some(err) = error {
         return err.into()
     };
     Ok(vec![])
 }
-pub const PUT: NaryOp = NaryOp::new("put", fput, 3);
+pub const PUT: VmOp = VmOp::new("put", fput, 3);
 
 fn flength(mut substack: Vec<Frame>) -> Result<Vec<Frame>, Error> {
-    let Frame::Passive(Passive::List(ref mut list)) = substack.pop().unwrap() else {
-        return Error::OpType.into()
+    let len = match substack.pop().unwrap() {
+        Frame::Passive(Passive::List(ref list)) => list.len()?,
+        Frame::Dict(ref dict) => dict.len()?,
+        Frame::Num(ref array) => array.length()?,
+        _ => return Error::OpType.into(),
     };
 
-    Ok(vec![Num::USize(Scalar(Value(list.len()?))).into()])
+    Ok(vec![Num::USize(Scalar(Value(len))).into()])
 }
 pub const LENGTH: NaryOp = NaryOp::new("length", flength, 1);
 
 fn fgetinterval(substack: Vec<Frame>) -> Result<Vec<Frame>, Error> {
-    let (Frame::Passive(Passive::List(ref list)),
-            Frame::Num(start),
-            Frame::Num(len))
+    match substack.into_iter().collect_tuple().unwrap() {
+        (Frame::Passive(Passive::List(ref list)), Frame::Num(start), Frame::Num(len)) =>
+            Ok(vec![Passive::List(list.range(from_num(start)?, from_num(len)?)?).into()]),
+        (Frame::Num(ref array), Frame::Num(start), Frame::Num(len)) =>
+            Ok(vec![array.range(from_num(start)?, from_num(len)?, 1)?.into()]),
+        _ => Error::OpType.into(),
+    }
+}
+pub const GETINTERVAL: NaryOp = NaryOp::new("getinterval", fgetinterval, 3);
+
+/// array start len stride slice: a view of every stride'th element.
+fn fslice(substack: Vec<Frame>) -> Result<Vec<Frame>, Error> {
+    let (Frame::Num(ref array), Frame::Num(start), Frame::Num(len), Frame::Num(stride))
         = substack.into_iter().collect_tuple().unwrap()
     else {return Error::OpType.into()};
 
-    Ok(vec![Passive::List(list.range(from_num(start)?, from_num(len)?)?).into()])
+    Ok(vec![array.range(from_num(start)?, from_num(len)?, from_num(stride)?)?.into()])
 }
-pub const GETINTERVAL: NaryOp = NaryOp::new("getinterval", fgetinterval, 3);
+pub const SLICE: NaryOp = NaryOp::new("slice", fslice, 4);
+
+fn fputinterval(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let error = match stack.iter().cloned().collect_tuple().unwrap() {
+        (Frame::Passive(Passive::List(ref values)),
+         Frame::Passive(Passive::List(ref mut list)),
+         Frame::Num(start)) => {
+            let start = from_num(start)?;
+            let len = values.len()?;
+            if start+len > list.len()? {
+                return Error::Range {len: list.len()?, index: start+len}.into()
+            };
+            let values = (0..len).map(|index| values.get(index)).collect::<Result<Vec<_>, _>>()?;
+            vm.keep(list)?;
+            values.into_iter()
+                .enumerate()
+                .find_map(|(index, value)| list.put(start+index, value))
+        },
+        (Frame::Num(values), Frame::Num(ref mut array), Frame::Num(start)) =>
+            array.put_interval(from_num(start)?, values),
+        _ => return Error::OpType.into(),
+    };
+
+    if let Some(err) = error {
+        return err.into()
+    };
+    Ok(vec![])
+}
+pub const PUTINTERVAL: VmOp = VmOp::new("putinterval", fputinterval, 3);
 
 fn fquit(_: Vec<Frame>) -> Result<Vec<Frame>, Error> {
     Error::Quit.into()
@@ -103,14 +161,7 @@ fn mkpass(mut stack: Vec<Frame>) -> Result<Vec<Frame>, Error> {
     let Frame::Active(active) = stack.pop().unwrap() else {
         return Error::OpType.into()
     };
-    let passive = match active {
-        Active::Name(name)     => Passive::Name(name),
-        Active::String(string) => Passive::String(string),
-        Active::Mark           => Passive::Mark,
-        Active::EndMark        => Passive::EndMark,
-        Active::List(list)     => Passive::List(list),
-    };
-    Ok(vec![passive.into()])
+    Ok(vec![Passive::from(active).into()])
 }
 pub const MKPASS: NaryOp = NaryOp::new("mkpass", mkpass, 1);
 
@@ -118,13 +169,33 @@ fn mkact(mut stack: Vec<Frame>) -> Result<Vec<Frame>, Error> {
     let Frame::Passive(passive) = stack.pop().unwrap() else {
         return Error::OpType.into()
     };
-    let active = match passive {
-        Passive::Name(name)     => Active::Name(name),
-        Passive::String(string) => Active::String(string),
-        Passive::Mark           => Active::Mark,
-        Passive::EndMark        => Active::EndMark,
-        Passive::List(list)     => Active::List(list),
-    };
-    Ok(vec![active.into()])
+    Ok(vec![Active::from(passive).into()])
 }
 pub const MKACT: NaryOp = NaryOp::new("mkact", mkact, 1);
+
+fn fxcheck(mut stack: Vec<Frame>) -> Result<Vec<Frame>, Error> {
+    let executable = matches!(stack.pop().unwrap(),
+        Frame::Active(_)
+        | Frame::UnaryOp(_) | Frame::BinaryOp(_) | Frame::StackOp(_)
+        | Frame::NaryOp(_) | Frame::VmOp(_) | Frame::Bound(_));
+    Ok(vec![Bool::from(executable).into()])
+}
+pub const XCHECK: NaryOp = NaryOp::new("xcheck", fxcheck, 1);
+
+fn fcvx(mut stack: Vec<Frame>) -> Result<Vec<Frame>, Error> {
+    let frame = match stack.pop().unwrap() {
+        Frame::Passive(passive) => Active::from(passive).into(),
+        frame => frame,
+    };
+    Ok(vec![frame])
+}
+pub const CVX: NaryOp = NaryOp::new("cvx", fcvx, 1);
+
+fn fcvlit(mut stack: Vec<Frame>) -> Result<Vec<Frame>, Error> {
+    let frame = match stack.pop().unwrap() {
+        Frame::Active(active) => Passive::from(active).into(),
+        frame => frame,
+    };
+    Ok(vec![frame])
+}
+pub const CVLIT: NaryOp = NaryOp::new("cvlit", fcvlit, 1);
@@ -5,23 +5,24 @@ use crate::error::Error;
 use super::optypes::VmOp;
 use super::naryops::from_num;
 
-fn name_op(stack: Vec<Frame>, vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+fn name_op(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
     let (frame, Frame::Passive(Passive::Name(name)))
-        = stack.into_iter().collect_tuple().unwrap()
+        = stack.iter().cloned().collect_tuple().unwrap()
     else {
         return Error::OpType.into()
     };
     
-    let Some(dict) = vm.dict_stack.front_mut() else {
+    let Some(mut dict) = vm.dict_stack.front().cloned() else {
         panic!("dict_stack empty")
     };
+    vm.keep(&dict)?;
     dict.put(name, frame);
     Ok(vec![])
 }
 pub const NAME: VmOp = VmOp::new("name", name_op, 2);
 
-fn mkname(mut stack: Vec<Frame>, vm: &mut Vm) -> Result<Vec<Frame>, Error> {
-    let Frame::Passive(Passive::String(string)) = stack.pop().unwrap() else {
+fn mkname(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let Frame::Passive(Passive::String(string)) = stack[0].clone() else {
         return Error::OpType.into()
     };
 
@@ -30,7 +31,7 @@ fn mkname(mut stack: Vec<Frame>, vm: &mut Vm) -> Result<Vec<Frame>, Error> {
 }
 pub const MKNAME: VmOp = VmOp::new("mkname", mkname, 1);
 
-fn mklist(_: Vec<Frame>, vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+fn mklist(_: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
     let mark: Frame = Passive::Mark.into();
     let r: Vec<Frame>
         = vm.op_stack.iter().cloned().rev()
@@ -53,7 +54,7 @@ fn mklist(_: Vec<Frame>, vm: &mut Vm) -> Result<Vec<Frame>, Error> {
 }
 pub const MKLIST: VmOp = VmOp::new("mklist", mklist, 0);
 
-fn mkproc(_: Vec<Frame>, vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+fn mkproc(_: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
     let mark: Frame = Active::Mark.into();
     let r: Vec<Frame>
         = vm.op_stack.iter().cloned().rev()
@@ -76,8 +77,8 @@ fn mkproc(_: Vec<Frame>, vm: &mut Vm) -> Result<Vec<Frame>, Error> {
 }
 pub const MKPROC: VmOp = VmOp::new("mkproc", mkproc, 0);
 
-fn flist(mut stack: Vec<Frame>, vm: &mut Vm) -> Result<Vec<Frame>, Error> {
-    let Frame::Num(n) = stack.pop().unwrap()
+fn flist(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let Frame::Num(n) = stack[0].clone()
     else {
         return Error::OpType.into()
     };
@@ -91,8 +92,8 @@ fn flist(mut stack: Vec<Frame>, vm: &mut Vm) -> Result<Vec<Frame>, Error> {
 }
 pub const LIST: VmOp = VmOp::new("list", flist, 1);
 
-fn op_exec(mut stack: Vec<Frame>, vm: &mut Vm) -> Result<Vec<Frame>, Error> {
-    let frame@Frame::Active(_) = stack.pop().unwrap() else {
+fn op_exec(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let frame@Frame::Active(_) = stack[0].clone() else {
         return Error::OpType.into()
     };
     
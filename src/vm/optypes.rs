@@ -1,7 +1,5 @@
 use std::fmt;
 
-use itertools::Itertools;
-
 use super::*;
 use crate::error::Error;
 
@@ -12,9 +10,13 @@ pub trait Op {
         (table.intern(s.into()), f)
     }
     fn from(&self) -> (&'static str, Frame);
+    fn name(&self) -> &'static str {
+        self.from().0
+    }
+    fn arity(&self) -> usize;
 }
 
-type VmOpFunc = fn(stack: Vec<Frame>, vm: &mut Vm) -> Result<Vec<Frame>, Error>;
+type VmOpFunc = fn(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error>;
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct VmOp {
     name: &'static str,
@@ -38,6 +40,8 @@ impl Op for VmOp {
     fn from(&self) -> (&'static str, Frame) {
         (self.name, Frame::from(*self))
     }
+
+    fn arity(&self) -> usize {self.n}
     
     fn exec(&self, vm: &mut Vm) -> Option<Error> {
         let n = self.n;
@@ -47,18 +51,36 @@ impl Op for VmOp {
             return Error::StackUnderflow.into()
         }
 
-        let len = len-n;
-        let substack = stack.split_off(len);
-        match (self.op)(substack, vm) {
-            Ok(mut substack) => {
-                vm.op_stack.append(&mut substack);
+        let operands = stack.split_off(len-n);
+        match (self.op)(&operands, vm) {
+            Ok(mut frames) => {
+                vm.op_stack.append(&mut frames);
                 None
             },
-            Err(error) => return Some(error),
+            Err(error) => {
+                vm.op_stack.extend(operands);
+                Some(error)
+            },
         }
     }
 }
 
+/// Lends num to an operator, which may work in place on an array
+/// nothing else holds; the copy kept to restore if it fails does not
+/// count as holding it.
+fn lend(num: &mut Num) -> Num {
+    let kept = num.clone();
+    num.lend(true);
+    kept
+}
+
+/// The result of an operator that was lent its operands, which may
+/// be one of them.
+fn given_back(mut num: Num) -> Frame {
+    num.lend(false);
+    num.into()
+}
+
 type UnaryOpFunc = fn(Num) -> Result<Num, Error>;
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct UnaryOp {
@@ -82,19 +104,29 @@ impl Op for UnaryOp {
     fn from(&self) -> (&'static str, Frame) {
         (self.name, Frame::from(*self))
     }
+
+    fn arity(&self) -> usize {1}
     
     fn exec(&self, vm: &mut Vm) -> Option<Error> {
-        let i = match vm.op_stack.pop() {
+        let mut i = match vm.op_stack.pop() {
             None => return Error::StackUnderflow.into(),
             Some(Frame::Num(num)) => num,
-            Some(_) => return Error::OpType.into(),
+            Some(frame) => {
+                vm.op_stack.push(frame);
+                return Error::OpType.into()
+            },
         };
+        let kept = lend(&mut i);
         match (self.op)(i) {
             Ok(r) => {
-                vm.op_stack.push(r.into());
+                drop(kept);
+                vm.op_stack.push(given_back(r));
                 None
             },
-            Err(e) => e.into(),
+            Err(e) => {
+                vm.op_stack.push(kept.into());
+                e.into()
+            },
         }
     }
 }
@@ -122,6 +154,8 @@ impl Op for BinaryOp {
     fn from(&self) -> (&'static str, Frame) {
         (self.name, Frame::from(*self))
     }
+
+    fn arity(&self) -> usize {2}
     
     fn exec(&self, vm: &mut Vm) -> Option<Error> {
         let stack = &mut vm.op_stack;
@@ -130,19 +164,24 @@ impl Op for BinaryOp {
             return Error::StackUnderflow.into()
         };
 
-        let substack = stack.split_off(len-2); 
-        let (Frame::Num(i1), Frame::Num(i2))
-            = substack.into_iter().collect_tuple().unwrap()
-        else {
+        let [Frame::Num(_), Frame::Num(_)] = &stack[len-2..] else {
+            return Error::OpType.into()
+        };
+        let (Some(Frame::Num(mut i2)), Some(Frame::Num(mut i1))) = (stack.pop(), stack.pop()) else {
             return Error::OpType.into()
         };
 
+        let kept = [lend(&mut i1), lend(&mut i2)];
         match (self.op)(i1, i2) {
             Ok(r) => {
-                stack.push(r.into());
+                drop(kept);
+                vm.op_stack.push(given_back(r));
                 None
             },
-            Err(e) => e.into(),
+            Err(e) => {
+                vm.op_stack.extend(kept.map(Frame::from));
+                e.into()
+            },
         }
     }
 }
@@ -171,6 +210,8 @@ impl Op for NaryOp {
     fn from(&self) -> (&'static str, Frame) {
         (self.name, Frame::from(*self))
     }
+
+    fn arity(&self) -> usize {self.n}
     
     fn exec(&self, vm: &mut Vm) -> Option<Error> {
         let stack = &mut vm.op_stack;
@@ -181,17 +222,18 @@ impl Op for NaryOp {
         }
 
         let len = len-n;
-        let substack = stack.split_off(len);
+        let substack = stack[len..].to_vec();
         let mut frames = match (self.op)(substack) {
             Ok(frames) => frames,
             Err(e) => return e.into(),
         };
+        stack.truncate(len);
         stack.append(&mut frames);
         None
     }
 }
 
-type StackOpFunc = fn(&Vec<Frame>, Vec<Frame>) -> Result<(Vec<Frame>, usize), Error>;
+type StackOpFunc = fn(&Vec<Frame>, &[Frame]) -> Result<(Vec<Frame>, usize), Error>;
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct StackOp {
     name: &'static str,
@@ -215,6 +257,8 @@ impl Op for StackOp {
     fn from(&self) -> (&'static str, Frame) {
         (self.name, Frame::from(*self))
     }
+
+    fn arity(&self) -> usize {self.n}
     
     fn exec(&self, vm: &mut Vm) -> Option<Error> {
         let stack = &mut vm.op_stack;
@@ -225,16 +269,20 @@ impl Op for StackOp {
         }
 
         let len = len-n;
-        let substack = stack.split_off(len);
-        let (mut substack, n) = match (self.op)(stack, substack) {
-            Ok((substack, n)) => (substack, n),
-            Err(e) => return e.into(),
+        let operands = stack.split_off(len);
+        let (mut result, n) = match (self.op)(stack, &operands) {
+            Ok((result, n)) => (result, n),
+            Err(e) => {
+                stack.extend(operands);
+                return e.into()
+            },
         };
         if len < n {
+            stack.extend(operands);
             return Error::StackUnderflow.into()
         };
         stack.truncate(len-n);
-        stack.append(&mut substack);
+        stack.append(&mut result);
         None
     }
 }
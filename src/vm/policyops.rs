@@ -0,0 +1,28 @@
+use super::*;
+use crate::error::Error;
+use super::optypes::VmOp;
+
+/// Sets how numeric results out of range or undefined are settled, by
+/// name: nan, error, saturate or wrap. The policy is kept with the save
+/// level and holds until the save level is restored; a NumericPolicy
+/// entry naming one in a dict on the dict stack overrides it while the
+/// dict is there, as for a procedure that begins a dict of its own.
+fn fsetnumericpolicy(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let Frame::Passive(Passive::Name(name)) = stack[0].clone() else {
+        return Error::OpType.into()
+    };
+    let name: &String = name.borrow();
+    let policy = name.parse::<Policy>()?;
+    if let Some(current) = vm.policy_stack.last_mut() {
+        *current = policy
+    };
+    Ok(vec![])
+}
+pub const SETNUMERICPOLICY: VmOp = VmOp::new("setnumericpolicy", fsetnumericpolicy, 1);
+
+fn fnumericpolicy(_: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let name = vm.policy()?.name();
+    let name = vm.intern(name.into());
+    Ok(vec![Passive::Name(name).into()])
+}
+pub const NUMERICPOLICY: VmOp = VmOp::new("numericpolicy", fnumericpolicy, 0);
@@ -0,0 +1,23 @@
+use super::optypes::UnaryOp;
+use crate::numeric::reduce::*;
+
+pub const SUM: UnaryOp     = UnaryOp::new("sum",     |a| a.apply_reduce::<SumOp>(false));
+pub const PROD: UnaryOp    = UnaryOp::new("prod",    |a| a.apply_reduce::<ProdOp>(false));
+pub const AMIN: UnaryOp    = UnaryOp::new("amin",    |a| a.apply_reduce::<AminOp>(false));
+pub const AMAX: UnaryOp    = UnaryOp::new("amax",    |a| a.apply_reduce::<AmaxOp>(false));
+pub const MEAN: UnaryOp    = UnaryOp::new("mean",    |a| a.apply_reduce::<MeanOp>(false));
+pub const VAR: UnaryOp     = UnaryOp::new("var",     |a| a.apply_reduce::<VarOp>(false));
+pub const STD: UnaryOp     = UnaryOp::new("std",     |a| a.apply_reduce::<StdOp>(false));
+pub const ARGMIN: UnaryOp  = UnaryOp::new("argmin",  |a| a.apply_reduce::<ArgminOp>(false));
+pub const ARGMAX: UnaryOp  = UnaryOp::new("argmax",  |a| a.apply_reduce::<ArgmaxOp>(false));
+pub const COUNT: UnaryOp   = UnaryOp::new("count",   |a| a.apply_reduce::<CountOp>(true));
+
+pub const NANSUM: UnaryOp    = UnaryOp::new("nansum",    |a| a.apply_reduce::<SumOp>(true));
+pub const NANPROD: UnaryOp   = UnaryOp::new("nanprod",   |a| a.apply_reduce::<ProdOp>(true));
+pub const NANAMIN: UnaryOp   = UnaryOp::new("nanamin",   |a| a.apply_reduce::<AminOp>(true));
+pub const NANAMAX: UnaryOp   = UnaryOp::new("nanamax",   |a| a.apply_reduce::<AmaxOp>(true));
+pub const NANMEAN: UnaryOp   = UnaryOp::new("nanmean",   |a| a.apply_reduce::<MeanOp>(true));
+pub const NANVAR: UnaryOp    = UnaryOp::new("nanvar",    |a| a.apply_reduce::<VarOp>(true));
+pub const NANSTD: UnaryOp    = UnaryOp::new("nanstd",    |a| a.apply_reduce::<StdOp>(true));
+pub const NANARGMIN: UnaryOp = UnaryOp::new("nanargmin", |a| a.apply_reduce::<ArgminOp>(true));
+pub const NANARGMAX: UnaryOp = UnaryOp::new("nanargmax", |a| a.apply_reduce::<ArgmaxOp>(true));
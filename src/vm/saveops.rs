@@ -0,0 +1,66 @@
+use std::collections::HashSet;
+
+use super::*;
+use crate::error::Error;
+use super::optypes::VmOp;
+use crate::types::savable::HasNew;
+
+fn fsave(_: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let save = SaveBox::base();
+    let handle = save.handle();
+    vm.save_stack.push(save);
+    vm.policy_stack.push(vm.save_policy());
+    Ok(vec![handle.into()])
+}
+pub const SAVE: VmOp = VmOp::new("save", fsave, 0);
+
+fn refers(frame: &Frame, addrs: &HashSet<usize>) -> bool {
+    let addr = match frame {
+        Frame::Active(Active::List(list))   => list.addr(),
+        Frame::Active(Active::Code(code))   => code.list().addr(),
+        Frame::Passive(Passive::List(list)) => list.addr(),
+        Frame::Dict(dict)                   => dict.addr(),
+        Frame::Control(control)             => match control.proc() {
+            Some(Active::List(list)) => list.addr(),
+            Some(Active::Code(code)) => code.list().addr(),
+            _ => return false,
+        },
+        _ => return false,
+    };
+    addrs.contains(&addr)
+}
+
+fn frestore(stack: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+    let Frame::Save(save) = stack[0].clone() else {
+        return Error::OpType.into()
+    };
+
+    let Some(level) = vm.save_stack.iter().skip(1).position(|s| *s == save) else {
+        return Error::InvalidRestore.into()
+    };
+    let level = level+1;
+
+    let mut addrs = HashSet::new();
+    for save in &vm.save_stack[level..] {
+        if let Some(err) = save.collect(&mut addrs) {
+            return err.into()
+        }
+    };
+
+    if vm.op_stack.iter().any(|frame| refers(frame, &addrs))
+        || vm.exec_stack.iter().any(|frame| refers(frame, &addrs))
+        || vm.dict_stack.iter().any(|dict| addrs.contains(&dict.addr()))
+    {
+        return Error::InvalidRestore.into()
+    };
+
+    // the newest first, so each object ends as it was at the save
+    for save in vm.save_stack[level..].iter().rev() {
+        save.undo()?;
+    };
+
+    vm.save_stack.truncate(level);
+    vm.policy_stack.truncate(level);
+    Ok(vec![])
+}
+pub const RESTORE: VmOp = VmOp::new("restore", frestore, 1);
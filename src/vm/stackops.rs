@@ -3,12 +3,12 @@ use crate::error::Error;
 use super::*;
 use super::optypes::StackOp;
 
-fn fclear(stack: &Vec<Frame>, _: Vec<Frame>) -> Result<(Vec<Frame>, usize), Error> {
+fn fclear(stack: &Vec<Frame>, _: &[Frame]) -> Result<(Vec<Frame>, usize), Error> {
     Ok((vec![], stack.len()))
 }
 pub const CLEAR: StackOp = StackOp::new("clear", fclear, 0);
 
-fn fshow(stack: &Vec<Frame>, _: Vec<Frame>) -> Result<(Vec<Frame>, usize), Error> {
+fn fshow(stack: &Vec<Frame>, _: &[Frame]) -> Result<(Vec<Frame>, usize), Error> {
     println!("Stack:");
     for v in stack.into_iter().rev() {
         println!("  {v}");
@@ -18,7 +18,7 @@ fn fshow(stack: &Vec<Frame>, _: Vec<Frame>) -> Result<(Vec<Frame>, usize), Error
 }
 pub const SHOW: StackOp = StackOp::new("show", fshow, 0);
 
-fn fpeek(stack: &Vec<Frame>, _: Vec<Frame>) -> Result<(Vec<Frame>, usize), Error>
+fn fpeek(stack: &Vec<Frame>, _: &[Frame]) -> Result<(Vec<Frame>, usize), Error>
 {
     match stack.last() {
         None => println!("Stack: empty"),
@@ -1,4 +1,25 @@
 use super::optypes::UnaryOp;
+use super::Num;
+use crate::numeric::ops_defs::*;
 
 pub const NEG: UnaryOp = UnaryOp::new("neg", |a| -a);
 pub const COS: UnaryOp = UnaryOp::new("cos", |a| a.cos());
+
+pub const SIN: UnaryOp   = UnaryOp::new("sin",   Num::apply_monadic::<SinOp>);
+pub const TAN: UnaryOp   = UnaryOp::new("tan",   Num::apply_monadic::<TanOp>);
+pub const ASIN: UnaryOp  = UnaryOp::new("asin",  Num::apply_monadic::<AsinOp>);
+pub const ACOS: UnaryOp  = UnaryOp::new("acos",  Num::apply_monadic::<AcosOp>);
+pub const ATAN: UnaryOp  = UnaryOp::new("atan",  Num::apply_monadic::<AtanOp>);
+pub const SINH: UnaryOp  = UnaryOp::new("sinh",  Num::apply_monadic::<SinhOp>);
+pub const COSH: UnaryOp  = UnaryOp::new("cosh",  Num::apply_monadic::<CoshOp>);
+pub const TANH: UnaryOp  = UnaryOp::new("tanh",  Num::apply_monadic::<TanhOp>);
+pub const EXP: UnaryOp   = UnaryOp::new("exp",   Num::apply_monadic::<ExpOp>);
+pub const LN: UnaryOp    = UnaryOp::new("ln",    Num::apply_monadic::<LnOp>);
+pub const LOG10: UnaryOp = UnaryOp::new("log10", Num::apply_monadic::<Log10Op>);
+pub const LOG2: UnaryOp  = UnaryOp::new("log2",  Num::apply_monadic::<Log2Op>);
+pub const SQRT: UnaryOp  = UnaryOp::new("sqrt",  Num::apply_monadic::<SqrtOp>);
+pub const ABS: UnaryOp   = UnaryOp::new("abs",   Num::abs);
+pub const FLOOR: UnaryOp = UnaryOp::new("floor", Num::apply_monadic::<FloorOp>);
+pub const CEIL: UnaryOp  = UnaryOp::new("ceil",  Num::apply_monadic::<CeilOp>);
+pub const ROUND: UnaryOp = UnaryOp::new("round", Num::apply_monadic::<RoundOp>);
+pub const TRUNC: UnaryOp = UnaryOp::new("trunc", Num::apply_monadic::<TruncOp>);
@@ -27,7 +27,7 @@ impl Vminfo {
 }
                 
 
-pub fn vmstatus(_: Vec<Frame>, vm: &mut Vm) -> Result<Vec<Frame>, Error> {
+pub fn vmstatus(_: &[Frame], vm: &mut Vm) -> Result<Vec<Frame>, Error> {
     let(max, used) = match vm.vminfo.0 {
         None => (-1, -1),
         Some(ref mut vminfo) => {